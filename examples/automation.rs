@@ -16,6 +16,7 @@
 use std::process::Command;
 
 /// Response structure for parsing tickrs JSON output
+#[allow(dead_code)]
 #[derive(Debug, serde::Deserialize)]
 struct JsonResponse<T> {
     success: bool,
@@ -35,6 +36,7 @@ struct ProjectListData {
     projects: Vec<Project>,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct Project {
@@ -43,12 +45,14 @@ struct Project {
     color: Option<String>,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, serde::Deserialize)]
 struct TaskListData {
     tasks: Vec<Task>,
     count: usize,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct Task {
@@ -21,6 +21,7 @@ use std::process::Command;
 // ============================================================================
 
 #[derive(Debug, Deserialize)]
+#[allow(dead_code)]
 struct JsonResponse<T> {
     success: bool,
     data: Option<T>,
@@ -3,123 +3,332 @@
 //! Implements the OAuth authorization code flow:
 //! 1. Generate authorization URL
 //! 2. Open browser for user to authorize
-//! 3. Capture callback with authorization code
+//! 3. Capture callback with authorization code (or let the user paste it,
+//!    when no browser is available)
 //! 4. Exchange code for access token
+//!
+//! The authorization request carries a PKCE (S256) code challenge, so an
+//! intercepted authorization code is useless without the verifier kept by
+//! the [`AuthHandler`] that created the URL. Token endpoints that reject the
+//! verifier are retried without it.
+//!
+//! Access tokens are refreshed with [`AuthHandler::refresh`] once they expire.
 
-use std::io::{BufRead, BufReader, Write};
-use std::net::TcpListener;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Context, Result};
-use oauth2::basic::BasicClient;
+use chrono::Utc;
+use oauth2::basic::{BasicClient, BasicErrorResponseType, BasicTokenResponse};
 use oauth2::{
-    AuthUrl, AuthorizationCode, ClientId, ClientSecret, CsrfToken, RedirectUrl, Scope,
-    TokenResponse, TokenUrl,
+    AuthUrl, AuthorizationCode, ClientId, ClientSecret, CsrfToken, PkceCodeChallenge,
+    PkceCodeVerifier, RedirectUrl, RefreshToken, RequestTokenError, Scope, TokenResponse, TokenUrl,
 };
 use reqwest::redirect::Policy;
+use tracing::{debug, warn};
 
-use crate::constants::{OAUTH_AUTH_URL, OAUTH_REDIRECT_URI, OAUTH_SCOPES, OAUTH_TOKEN_URL};
+use crate::api::http;
+use crate::config::{Endpoints, HttpConfig, OAuthConfig, TokenSet};
+use crate::constants::OAUTH_SCOPES;
+use crate::utils::logging;
 
 /// OAuth authentication handler
+#[derive(Clone)]
 pub struct AuthHandler {
     client_id: String,
     client_secret: String,
+    http: HttpConfig,
+    endpoints: Endpoints,
+    callback: OAuthConfig,
+    scopes: Vec<String>,
+    /// PKCE verifiers of issued authorization URLs, by CSRF state
+    pkce_verifiers: Arc<Mutex<HashMap<String, PkceCodeVerifier>>>,
 }
 
+/// How often the callback server checks its deadline while idle
+const CALLBACK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long a connection to the callback server may take to send its request
+const CALLBACK_READ_TIMEOUT: Duration = Duration::from_secs(10);
+
 impl AuthHandler {
     /// Create a new auth handler with client credentials
     pub fn new(client_id: String, client_secret: String) -> Self {
+        logging::register_secret(&client_secret);
         Self {
             client_id,
             client_secret,
+            http: HttpConfig::default(),
+            endpoints: Endpoints::default(),
+            callback: OAuthConfig::default(),
+            scopes: OAUTH_SCOPES.iter().map(|s| s.to_string()).collect(),
+            pkce_verifiers: Arc::default(),
         }
     }
 
-    /// Generate the authorization URL for the user to visit
+    /// Use the OAuth endpoints of a region or a custom server
+    pub fn with_endpoints(mut self, endpoints: Endpoints) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Use the given timeouts, proxy and root certificates for the token exchange
+    pub fn with_http_config(mut self, http: HttpConfig) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Use the given redirect URI host/port, callback timeout and PKCE setting
+    pub fn with_callback_config(mut self, callback: OAuthConfig) -> Self {
+        self.callback = callback;
+        self
+    }
+
+    /// Request these scopes instead of [`OAUTH_SCOPES`] (e.g. only
+    /// `tasks:read` for `init --read-only`)
+    pub fn with_scopes(mut self, scopes: &[&str]) -> Self {
+        self.scopes = scopes.iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    /// Generate the authorization URL for the user to visit.
+    ///
+    /// Unless PKCE is disabled, the URL carries an S256 code challenge. Its
+    /// verifier is kept by this handler until the code with the returned
+    /// state is exchanged.
     pub fn get_auth_url(&self) -> Result<(String, CsrfToken)> {
         let client = self.create_oauth_client()?;
 
         let mut auth_request = client.authorize_url(CsrfToken::new_random);
 
         // Add scopes
-        for scope in OAUTH_SCOPES {
-            auth_request = auth_request.add_scope(Scope::new((*scope).to_string()));
+        for scope in &self.scopes {
+            auth_request = auth_request.add_scope(Scope::new(scope.clone()));
         }
 
+        let pkce_verifier = if self.callback.pkce {
+            let (challenge, verifier) = PkceCodeChallenge::new_random_sha256();
+            auth_request = auth_request.set_pkce_challenge(challenge);
+            Some(verifier)
+        } else {
+            None
+        };
+
         let (auth_url, csrf_token) = auth_request.url();
+        if let Some(verifier) = pkce_verifier {
+            logging::register_secret(verifier.secret());
+            self.pkce_verifiers
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(csrf_token.secret().clone(), verifier);
+        }
         Ok((auth_url.to_string(), csrf_token))
     }
 
     /// Run the full OAuth flow: open browser, capture callback, exchange code
-    pub async fn run_oauth_flow(&self) -> Result<String> {
+    #[allow(dead_code)] // Available for external use
+    pub async fn run_oauth_flow(&self) -> Result<TokenSet> {
         let (auth_url, csrf_token) = self.get_auth_url()?;
 
         // Try to open browser, but don't fail if it can't open (e.g., headless environments)
         let _ = webbrowser::open(&auth_url);
 
+        self.complete_with_callback(&csrf_token).await
+    }
+
+    /// Wait for the browser to be redirected to localhost, then exchange the
+    /// authorization code from that redirect
+    pub async fn complete_with_callback(&self, csrf_token: &CsrfToken) -> Result<TokenSet> {
         // Wait for callback with authorization code
         let code = self.capture_callback(csrf_token)?;
 
         // Exchange code for token
-        let token = self.exchange_code(&code).await?;
-
-        Ok(token)
+        self.exchange_code(&code, csrf_token).await
     }
 
-    /// Capture the OAuth callback on localhost
-    fn capture_callback(&self, expected_csrf: CsrfToken) -> Result<String> {
-        // Bind to localhost:8080
-        let listener = TcpListener::bind("127.0.0.1:8080")
-            .context("Failed to bind to localhost:8080. Is another process using this port?")?;
-
-        // Accept a single connection
-        let (mut stream, _) = listener
-            .accept()
-            .context("Failed to accept OAuth callback connection")?;
-
-        // Read the request
-        let mut reader = BufReader::new(&stream);
-        let mut request_line = String::new();
-        reader
-            .read_line(&mut request_line)
-            .context("Failed to read OAuth callback request")?;
-
-        // Parse the request to extract code and state
-        let (code, state) = parse_callback_request(&request_line)?;
+    /// Exchange an authorization code pasted by the user.
+    ///
+    /// `input` is the URL the browser was redirected to, or its query string,
+    /// whose `state` must match `csrf_token`. With PKCE, it may also be the
+    /// bare code: only this handler knows the verifier it must be exchanged
+    /// with.
+    pub async fn complete_with_pasted_code(
+        &self,
+        input: &str,
+        csrf_token: &CsrfToken,
+    ) -> Result<TokenSet> {
+        let has_verifier = self
+            .pkce_verifiers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains_key(csrf_token.secret());
+        let code = parse_pasted_code(input, csrf_token, has_verifier)?;
+        logging::register_secret(&code);
+        self.exchange_code(&code, csrf_token).await
+    }
 
-        // Verify CSRF token
-        if state != *expected_csrf.secret() {
-            // Send error response
-            let response = create_error_response("CSRF token mismatch - possible security issue");
-            stream.write_all(response.as_bytes())?;
-            return Err(anyhow!(
-                "CSRF token mismatch - authorization may have been tampered with"
-            ));
+    /// Capture the OAuth callback on localhost.
+    ///
+    /// Requests that are not the expected redirect (e.g. `/favicon.ico`, or
+    /// one with a different `state`) are answered with `404` and ignored, so
+    /// this keeps listening until a valid redirect arrives or the configured
+    /// timeout expires.
+    fn capture_callback(&self, expected_csrf: &CsrfToken) -> Result<String> {
+        self.callback.validate()?;
+        let (host, port) = self.callback.bind_address();
+        let listener = TcpListener::bind((host.as_str(), port)).with_context(|| {
+            format!(
+                "Failed to bind to {}:{}. Is another process using this port? \
+                 Choose another one with --callback-port (and update the redirect URI of your TickTick app).",
+                host, port
+            )
+        })?;
+        listener
+            .set_nonblocking(true)
+            .context("Failed to configure OAuth callback server")?;
+        debug!("Waiting for OAuth callback on {}:{}", host, port);
+
+        let deadline = self
+            .callback
+            .callback_timeout()
+            .map(|timeout| Instant::now() + timeout);
+
+        loop {
+            let stream = match listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                        return Err(anyhow!(
+                            "Timed out after {}s waiting for the authorization redirect to {}. \
+                             Run 'tickrs init' again, or use 'tickrs init --no-browser' to paste the redirect URL.",
+                            self.callback.callback_timeout_secs,
+                            self.callback.redirect_uri()
+                        ));
+                    }
+                    std::thread::sleep(CALLBACK_POLL_INTERVAL);
+                    continue;
+                }
+                Err(e) => return Err(e).context("Failed to accept OAuth callback connection"),
+            };
+
+            match handle_callback_connection(stream, expected_csrf) {
+                Ok(Some(code)) => return Ok(code),
+                Ok(None) => {}
+                Err(e) if self.callback.pkce && e.to_string().contains("invalid_request") => {
+                    return Err(anyhow!(
+                        "{}. If the server doesn't support PKCE, set 'pkce = false' in the [oauth] section of the config file.",
+                        e
+                    ));
+                }
+                Err(e) => return Err(e),
+            }
         }
-
-        // Send success response
-        let response = create_success_response();
-        stream.write_all(response.as_bytes())?;
-
-        Ok(code)
     }
 
     /// Exchange authorization code for access token
-    async fn exchange_code(&self, code: &str) -> Result<String> {
+    ///
+    /// The PKCE verifier of the authorization URL with `csrf_token` is sent
+    /// along. If the server rejects the request as invalid, which servers
+    /// without PKCE support may do, the exchange is retried without it.
+    async fn exchange_code(&self, code: &str, csrf_token: &CsrfToken) -> Result<TokenSet> {
         let client = self.create_oauth_client()?;
+        let http_client = self.token_http_client()?;
+        let code = AuthorizationCode::new(code.to_string());
+
+        let pkce_verifier = self
+            .pkce_verifiers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(csrf_token.secret());
+
+        let token_result = match pkce_verifier {
+            Some(verifier) => {
+                let result = client
+                    .exchange_code(code.clone())
+                    .set_pkce_verifier(verifier)
+                    .request_async(&http_client)
+                    .await;
+                match result {
+                    Err(RequestTokenError::ServerResponse(response))
+                        if *response.error() == BasicErrorResponseType::InvalidRequest =>
+                    {
+                        warn!(
+                            "Token endpoint rejected the PKCE code verifier ({}), retrying without PKCE",
+                            response
+                        );
+                        client.exchange_code(code).request_async(&http_client).await
+                    }
+                    result => result,
+                }
+            }
+            None => client.exchange_code(code).request_async(&http_client).await,
+        }
+        .context("Failed to exchange authorization code for token")?;
 
-        // Create HTTP client with no redirects for SSRF protection
-        let http_client = reqwest::Client::builder()
-            .redirect(Policy::none())
-            .build()
-            .context("Failed to create HTTP client")?;
+        debug!("Exchanged authorization code for access token");
+        let mut tokens = self.token_set(&token_result);
+        if tokens.scopes.is_empty() {
+            tokens.scopes = self.scopes.clone();
+        }
+        Ok(tokens)
+    }
+
+    /// Obtain a new access token with a refresh token.
+    ///
+    /// Fields the server doesn't return again (refresh token, scopes) are
+    /// left empty for the caller to carry over.
+    pub async fn refresh(&self, refresh_token: &str) -> Result<TokenSet> {
+        let client = self.create_oauth_client()?;
+        let http_client = self.token_http_client()?;
 
         let token_result = client
-            .exchange_code(AuthorizationCode::new(code.to_string()))
+            .exchange_refresh_token(&RefreshToken::new(refresh_token.to_string()))
             .request_async(&http_client)
             .await
-            .context("Failed to exchange authorization code for token")?;
+            .context("Failed to refresh access token")?;
 
-        Ok(token_result.access_token().secret().clone())
+        debug!("Refreshed access token");
+        Ok(self.token_set(&token_result))
+    }
+
+    /// HTTP client for the token endpoint
+    fn token_http_client(&self) -> Result<reqwest::Client> {
+        // No redirects for SSRF protection
+        http::client_builder(&self.http)?
+            .redirect(Policy::none())
+            .build()
+            .context("Failed to create HTTP client")
+    }
+
+    /// Convert a token endpoint response, recording the client ID so the
+    /// tokens can be refreshed later. The client secret isn't recorded; it is
+    /// read from `TICKTICK_CLIENT_SECRET` when refreshing.
+    fn token_set(&self, response: &BasicTokenResponse) -> TokenSet {
+        let access_token = response.access_token().secret().clone();
+        logging::register_secret(&access_token);
+
+        let refresh_token = response.refresh_token().map(|t| t.secret().clone());
+        if let Some(refresh_token) = &refresh_token {
+            logging::register_secret(refresh_token);
+        }
+
+        TokenSet {
+            access_token,
+            refresh_token,
+            expires_at: response
+                .expires_in()
+                .and_then(|expires_in| chrono::Duration::from_std(expires_in).ok())
+                .map(|expires_in| Utc::now() + expires_in),
+            scopes: response
+                .scopes()
+                .map(|scopes| scopes.iter().map(|s| s.to_string()).collect())
+                .unwrap_or_default(),
+            client_id: Some(self.client_id.clone()),
+            client_secret: None,
+        }
     }
 
     /// Create the OAuth2 client with auth and token URLs configured
@@ -149,18 +358,103 @@ impl AuthHandler {
         let client = BasicClient::new(ClientId::new(self.client_id.clone()))
             .set_client_secret(ClientSecret::new(self.client_secret.clone()))
             .set_auth_uri(
-                AuthUrl::new(OAUTH_AUTH_URL.to_string()).context("Invalid authorization URL")?,
+                AuthUrl::new(self.endpoints.oauth_auth_url.clone())
+                    .context("Invalid authorization URL")?,
+            )
+            .set_token_uri(
+                TokenUrl::new(self.endpoints.oauth_token_url.clone())
+                    .context("Invalid token URL")?,
             )
-            .set_token_uri(TokenUrl::new(OAUTH_TOKEN_URL.to_string()).context("Invalid token URL")?)
             .set_redirect_uri(
-                RedirectUrl::new(OAUTH_REDIRECT_URI.to_string()).context("Invalid redirect URI")?,
+                RedirectUrl::new(self.callback.redirect_uri()).context("Invalid redirect URI")?,
             );
 
         Ok(client)
     }
 }
 
+/// A request received by the callback server
+#[derive(Debug, PartialEq, Eq)]
+enum CallbackRequest {
+    /// The redirect with the authorization code and the expected state
+    Code(String),
+    /// The redirect reporting that authorization failed (e.g. access denied)
+    Failed(String),
+    /// A redirect whose state doesn't match, possibly forged
+    StateMismatch,
+    /// Anything else, such as `/favicon.ico`
+    Ignored,
+}
+
+/// Classify a callback request line against the expected CSRF token
+fn classify_callback_request(request_line: &str, expected_csrf: &CsrfToken) -> CallbackRequest {
+    let Some(path) = request_line.split_whitespace().nth(1) else {
+        return CallbackRequest::Ignored;
+    };
+
+    if let Some(state) = extract_param(path, "state") {
+        if verify_state(&state, expected_csrf).is_err() {
+            return CallbackRequest::StateMismatch;
+        }
+    }
+
+    match parse_callback_path(path) {
+        Ok((code, _)) => CallbackRequest::Code(code),
+        Err(e) if extract_param(path, "error").is_some() => CallbackRequest::Failed(e.to_string()),
+        Err(_) => CallbackRequest::Ignored,
+    }
+}
+
+/// Read one request from the callback server and answer it.
+///
+/// Returns the authorization code, `None` for ignored requests, or the
+/// authorization error reported by the server.
+fn handle_callback_connection(
+    mut stream: TcpStream,
+    expected_csrf: &CsrfToken,
+) -> Result<Option<String>> {
+    // Accepted sockets may inherit the listener's non-blocking mode
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(CALLBACK_READ_TIMEOUT))?;
+
+    // Read the request; connections that send nothing (e.g. browser preconnects) are skipped
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    if let Err(e) = reader.read_line(&mut request_line) {
+        debug!("Ignoring OAuth callback connection: {}", e);
+        return Ok(None);
+    }
+
+    debug!("OAuth callback request: {}", request_line.trim_end());
+
+    match classify_callback_request(&request_line, expected_csrf) {
+        CallbackRequest::Code(code) => {
+            logging::register_secret(&code);
+            stream.write_all(create_success_response().as_bytes())?;
+            Ok(Some(code))
+        }
+        CallbackRequest::Failed(message) => {
+            stream.write_all(create_error_response(&message).as_bytes())?;
+            Err(anyhow!(message))
+        }
+        CallbackRequest::StateMismatch => {
+            warn!(
+                "Ignoring OAuth callback with a state that doesn't match the authorization request"
+            );
+            let response = create_error_response("CSRF token mismatch - possible security issue");
+            let _ = stream.write_all(response.as_bytes());
+            Ok(None)
+        }
+        CallbackRequest::Ignored => {
+            debug!("Ignoring request that is not the OAuth callback");
+            let _ = stream.write_all(create_not_found_response().as_bytes());
+            Ok(None)
+        }
+    }
+}
+
 /// Parse the OAuth callback request to extract code and state
+#[allow(dead_code)] // Available for external use
 fn parse_callback_request(request_line: &str) -> Result<(String, String)> {
     // Request line format: "GET /?code=xxx&state=yyy HTTP/1.1"
     let parts: Vec<&str> = request_line.split_whitespace().collect();
@@ -168,8 +462,54 @@ fn parse_callback_request(request_line: &str) -> Result<(String, String)> {
         return Err(anyhow!("Invalid callback request format"));
     }
 
-    let path = parts[1];
+    parse_callback_path(parts[1])
+}
+
+/// Parse the code from user input: a redirect URL, its query string, or the
+/// bare code.
+///
+/// The state of a pasted redirect must match the expected CSRF token. A bare
+/// code carries no state, so it is only accepted if it is tied to this
+/// session by a PKCE verifier (`has_verifier`).
+fn parse_pasted_code(input: &str, expected_csrf: &CsrfToken, has_verifier: bool) -> Result<String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(anyhow!("No authorization code entered"));
+    }
 
+    if !input.contains('=') {
+        if has_verifier {
+            return Ok(input.to_string());
+        }
+        return Err(anyhow!(
+            "Paste the full URL you were redirected to, including its state \
+             (http://localhost:8080/?code=...&state=...). A bare code is only accepted with PKCE, \
+             which is disabled in the [oauth] section of the config file"
+        ));
+    }
+
+    // Accept "http://localhost:8080/?code=...&state=..." as well as "code=...&state=..."
+    let (code, state) = if input.contains('?') {
+        parse_callback_path(input)?
+    } else {
+        parse_callback_path(&format!("?{}", input))?
+    };
+    verify_state(&state, expected_csrf)?;
+    Ok(code)
+}
+
+/// Check the `state` of a callback against the CSRF token of the authorization URL
+fn verify_state(state: &str, expected_csrf: &CsrfToken) -> Result<()> {
+    if state != expected_csrf.secret() {
+        return Err(anyhow!(
+            "CSRF token mismatch - authorization may have been tampered with"
+        ));
+    }
+    Ok(())
+}
+
+/// Extract code and state from a callback path such as `/?code=xxx&state=yyy`
+fn parse_callback_path(path: &str) -> Result<(String, String)> {
     // Check for error in callback
     if path.contains("error=") {
         let error_desc = extract_param(path, "error_description")
@@ -244,6 +584,11 @@ fn create_success_response() -> String {
     )
 }
 
+/// Create the response for requests that are not the OAuth redirect
+fn create_not_found_response() -> String {
+    "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+}
+
 /// Create an HTTP error response
 fn create_error_response(message: &str) -> String {
     let body = format!(
@@ -322,6 +667,141 @@ mod tests {
         assert_eq!(extract_param(path, "missing"), None);
     }
 
+    #[test]
+    fn test_parse_pasted_redirect_url() {
+        let csrf = CsrfToken::new("expected-state".to_string());
+        let code = parse_pasted_code(
+            "  http://localhost:8080/?code=abc123&state=expected-state\n",
+            &csrf,
+            true,
+        )
+        .unwrap();
+        assert_eq!(code, "abc123");
+
+        let code = parse_pasted_code("code=abc123&state=expected-state", &csrf, true).unwrap();
+        assert_eq!(code, "abc123");
+    }
+
+    #[test]
+    fn test_parse_pasted_redirect_url_checks_state() {
+        let csrf = CsrfToken::new("expected-state".to_string());
+        let err = parse_pasted_code("http://localhost:8080/?code=abc&state=other", &csrf, true)
+            .unwrap_err();
+        assert!(err.to_string().contains("CSRF token mismatch"));
+
+        assert!(parse_pasted_code("http://localhost:8080/?code=abc", &csrf, true).is_err());
+        assert!(
+            parse_pasted_code("http://localhost:8080/?error=access_denied", &csrf, true).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_pasted_bare_code_requires_pkce() {
+        let csrf = CsrfToken::new("expected-state".to_string());
+        assert_eq!(
+            parse_pasted_code(" abc123 \n", &csrf, true).unwrap(),
+            "abc123"
+        );
+        assert!(parse_pasted_code("  \n", &csrf, true).is_err());
+
+        let err = parse_pasted_code(" abc123 \n", &csrf, false).unwrap_err();
+        assert!(err.to_string().contains("including its state"));
+    }
+
+    #[test]
+    fn test_classify_callback_request() {
+        let csrf = CsrfToken::new("expected-state".to_string());
+        assert_eq!(
+            classify_callback_request("GET /?code=abc&state=expected-state HTTP/1.1", &csrf),
+            CallbackRequest::Code("abc".to_string())
+        );
+        assert_eq!(
+            classify_callback_request("GET /favicon.ico HTTP/1.1", &csrf),
+            CallbackRequest::Ignored
+        );
+        assert_eq!(
+            classify_callback_request("GET /?code=abc HTTP/1.1", &csrf),
+            CallbackRequest::Ignored
+        );
+        assert_eq!(
+            classify_callback_request("", &csrf),
+            CallbackRequest::Ignored
+        );
+        assert_eq!(
+            classify_callback_request("GET /?code=abc&state=forged HTTP/1.1", &csrf),
+            CallbackRequest::StateMismatch
+        );
+        assert_eq!(
+            classify_callback_request("GET /?error=access_denied&state=forged HTTP/1.1", &csrf),
+            CallbackRequest::StateMismatch
+        );
+        assert!(matches!(
+            classify_callback_request(
+                "GET /?error=access_denied&state=expected-state HTTP/1.1",
+                &csrf
+            ),
+            CallbackRequest::Failed(message) if message.contains("access_denied")
+        ));
+    }
+
+    #[test]
+    fn test_capture_callback_ignores_stray_requests() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+
+        let handler = AuthHandler::new("id".to_string(), "secret".to_string())
+            .with_callback_config(OAuthConfig {
+                callback_host: "127.0.0.1".to_string(),
+                callback_port: port,
+                callback_timeout_secs: 10,
+                ..OAuthConfig::default()
+            });
+        let csrf = CsrfToken::new("expected-state".to_string());
+
+        let client = std::thread::spawn(move || {
+            let send = |request: &str| loop {
+                if let Ok(mut stream) = TcpStream::connect(("127.0.0.1", port)) {
+                    stream.write_all(request.as_bytes()).unwrap();
+                    let mut response = String::new();
+                    let _ = std::io::Read::read_to_string(&mut stream, &mut response);
+                    return response;
+                }
+                std::thread::sleep(Duration::from_millis(20));
+            };
+            let favicon = send("GET /favicon.ico HTTP/1.1\r\n\r\n");
+            let forged = send("GET /?code=forged&state=other HTTP/1.1\r\n\r\n");
+            let valid = send("GET /?code=real-code&state=expected-state HTTP/1.1\r\n\r\n");
+            (favicon, forged, valid)
+        });
+
+        let code = handler.capture_callback(&csrf).unwrap();
+        let (favicon, forged, valid) = client.join().unwrap();
+        assert_eq!(code, "real-code");
+        assert!(favicon.starts_with("HTTP/1.1 404"));
+        assert!(forged.starts_with("HTTP/1.1 400"));
+        assert!(valid.starts_with("HTTP/1.1 200"));
+    }
+
+    #[test]
+    fn test_capture_callback_times_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+
+        let handler = AuthHandler::new("id".to_string(), "secret".to_string())
+            .with_callback_config(OAuthConfig {
+                callback_host: "127.0.0.1".to_string(),
+                callback_port: port,
+                callback_timeout_secs: 1,
+                ..OAuthConfig::default()
+            });
+        let csrf = CsrfToken::new("expected-state".to_string());
+
+        let err = handler.capture_callback(&csrf).unwrap_err();
+        assert!(err.to_string().contains("Timed out after 1s"));
+    }
+
     #[test]
     fn test_urlencoding_decode() {
         assert_eq!(urlencoding_decode("hello%20world"), "hello world");
@@ -0,0 +1,99 @@
+//! Concurrent request execution
+//!
+//! [`TickTickClient::batch`](crate::api::TickTickClient::batch) runs many
+//! requests at once, limited to a maximum number in flight. All requests made
+//! through a client (and its clones) also share a [`RateLimiter`], so a
+//! large fan-out doesn't exceed the configured request rate.
+
+use std::time::Duration;
+
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+/// Default number of requests in flight for batch operations
+pub const DEFAULT_MAX_IN_FLIGHT: usize = 8;
+
+/// Default request rate limit (requests per second, 0 = unlimited)
+pub const DEFAULT_REQUESTS_PER_SECOND: u32 = 10;
+
+/// Spaces requests evenly so that at most `requests_per_second` start per second
+#[derive(Debug)]
+pub struct RateLimiter {
+    interval: Option<Duration>,
+    next_slot: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    /// Create a limiter allowing `requests_per_second` requests (0 = unlimited)
+    pub fn new(requests_per_second: u32) -> Self {
+        let interval =
+            (requests_per_second > 0).then(|| Duration::from_secs(1) / requests_per_second);
+        Self {
+            interval,
+            next_slot: Mutex::new(None),
+        }
+    }
+
+    /// A limiter that never delays requests
+    pub fn unlimited() -> Self {
+        Self::new(0)
+    }
+
+    /// Wait until the next request may be sent
+    pub async fn acquire(&self) {
+        let Some(interval) = self.interval else {
+            return;
+        };
+
+        let wait_until = {
+            let mut next_slot = self.next_slot.lock().await;
+            let now = Instant::now();
+            let slot = next_slot.map_or(now, |slot| slot.max(now));
+            *next_slot = Some(slot + interval);
+            slot
+        };
+        tokio::time::sleep_until(wait_until).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limiter_spaces_requests() {
+        let limiter = RateLimiter::new(4);
+        let start = Instant::now();
+
+        for _ in 0..5 {
+            limiter.acquire().await;
+        }
+
+        // First request is immediate, the next four are 250ms apart
+        assert_eq!(start.elapsed(), Duration::from_secs(1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_unlimited_rate_limiter_never_waits() {
+        let limiter = RateLimiter::unlimited();
+        let start = Instant::now();
+
+        for _ in 0..100 {
+            limiter.acquire().await;
+        }
+
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limiter_does_not_accumulate_idle_budget() {
+        let limiter = RateLimiter::new(2);
+        limiter.acquire().await;
+        tokio::time::sleep(Duration::from_secs(10)).await;
+
+        let start = Instant::now();
+        limiter.acquire().await;
+        limiter.acquire().await;
+        assert_eq!(start.elapsed(), Duration::from_millis(500));
+    }
+}
@@ -0,0 +1,333 @@
+//! On-disk cache for read-only API responses
+//!
+//! Project lists and project data are requested by almost every command (e.g.
+//! resolving `--project-name` and then listing its tasks). [`ResponseCache`]
+//! stores those responses under `Config::data_dir()/cache` for a short time,
+//! so scripts running many commands in a row don't repeat the same requests.
+//!
+//! Entries are kept per account (API base URL and token), and all entries of
+//! an account are dropped whenever a client makes a mutating request.
+//!
+//! Next to the entries, each account has a [`ProjectNames`] map used to
+//! resolve `--project-name` without a request. It expires after the same
+//! TTL as the entries. Task changes don't affect project names, so it
+//! survives invalidation; creating, updating or deleting a project drops it.
+
+use std::collections::hash_map::DefaultHasher;
+use std::fs::{self, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::api::project_names::ProjectNames;
+use crate::config::Config;
+
+/// Default time a cached response stays valid
+pub const DEFAULT_CACHE_TTL_SECS: u64 = 30;
+
+/// Set by `--no-cache`, makes clients ignore cached entries
+static BYPASS: AtomicBool = AtomicBool::new(false);
+
+/// Ignore cached entries in all clients created afterwards (`--no-cache`).
+///
+/// Responses are still written to the cache and mutations still invalidate
+/// it, so later cached reads stay consistent.
+pub fn set_bypass(bypass: bool) {
+    BYPASS.store(bypass, Ordering::Relaxed);
+}
+
+/// Whether cached entries are currently ignored
+pub fn is_bypassed() -> bool {
+    BYPASS.load(Ordering::Relaxed)
+}
+
+/// A cached response body
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    /// Endpoint the response belongs to
+    endpoint: String,
+    /// Seconds since the Unix epoch when the response was stored
+    stored_at: u64,
+    /// Raw JSON response body
+    body: String,
+}
+
+/// Pseudo endpoint of the project name map's entry
+const PROJECT_NAMES_ENTRY: &str = "project_names";
+
+/// Time-limited cache of GET responses for one account
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+    bypass: bool,
+}
+
+impl ResponseCache {
+    /// Create a cache storing entries in `dir` for `ttl`
+    pub fn new(dir: impl Into<PathBuf>, ttl: Duration) -> Self {
+        Self {
+            dir: dir.into(),
+            ttl,
+            bypass: false,
+        }
+    }
+
+    /// Create the cache for an account below `Config::data_dir()/cache`
+    pub fn for_account(base_url: &str, token: &str, ttl: Duration) -> Result<Self> {
+        let mut hasher = DefaultHasher::new();
+        base_url.hash(&mut hasher);
+        token.hash(&mut hasher);
+        let dir = Self::root_dir()?.join(format!("{:016x}", hasher.finish()));
+        Ok(Self::new(dir, ttl))
+    }
+
+    /// Directory containing the caches of all accounts
+    pub fn root_dir() -> Result<PathBuf> {
+        Ok(Config::data_dir()?.join("cache"))
+    }
+
+    /// Ignore existing entries, but keep storing and invalidating them
+    pub fn with_bypass(mut self, bypass: bool) -> Self {
+        self.bypass = bypass;
+        self
+    }
+
+    /// Directory holding this cache's entries
+    #[allow(dead_code)] // Available for external use
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Return the cached body for an endpoint if it is younger than the TTL
+    pub fn get(&self, endpoint: &str) -> Option<String> {
+        self.read_entry(&self.entry_path(endpoint), endpoint)
+    }
+
+    fn read_entry(&self, path: &Path, endpoint: &str) -> Option<String> {
+        if self.bypass {
+            return None;
+        }
+
+        let data = fs::read(path).ok()?;
+        let entry: CacheEntry = serde_json::from_slice(&data).ok()?;
+        if entry.endpoint != endpoint {
+            return None;
+        }
+
+        let age = now_secs().checked_sub(entry.stored_at)?;
+        if Duration::from_secs(age) >= self.ttl {
+            return None;
+        }
+
+        debug!("Cache hit for {} (age {}s)", endpoint, age);
+        Some(entry.body)
+    }
+
+    /// Store a response body. Failures are logged and otherwise ignored.
+    pub fn put(&self, endpoint: &str, body: &str) {
+        if let Err(e) = self.write_entry(&self.entry_path(endpoint), endpoint, body) {
+            debug!("Failed to cache response for {}: {:#}", endpoint, e);
+        }
+    }
+
+    /// Drop all entries. Failures are logged and otherwise ignored.
+    pub fn invalidate(&self) {
+        match fs::remove_dir_all(&self.dir) {
+            Ok(()) => debug!("Invalidated response cache"),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => debug!("Failed to invalidate response cache: {}", e),
+        }
+    }
+
+    /// Return the account's project name map if it is younger than the TTL
+    /// and entries aren't ignored
+    pub fn project_names(&self) -> Option<ProjectNames> {
+        let body = self.read_entry(&self.project_names_path(), PROJECT_NAMES_ENTRY)?;
+        serde_json::from_str(&body).ok()
+    }
+
+    /// Store the account's project name map. Failures are logged and
+    /// otherwise ignored.
+    pub fn put_project_names(&self, names: &ProjectNames) {
+        let result = serde_json::to_string(names)
+            .map_err(anyhow::Error::from)
+            .and_then(|body| {
+                self.write_entry(&self.project_names_path(), PROJECT_NAMES_ENTRY, &body)
+            });
+        if let Err(e) = result {
+            debug!("Failed to cache project names: {:#}", e);
+        }
+    }
+
+    /// Drop the account's project name map. Failures are logged and
+    /// otherwise ignored.
+    pub fn forget_project_names(&self) {
+        match fs::remove_file(self.project_names_path()) {
+            Ok(()) => debug!("Dropped cached project names"),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => debug!("Failed to drop cached project names: {}", e),
+        }
+    }
+
+    /// Kept outside the entry directory, which invalidation removes
+    fn project_names_path(&self) -> PathBuf {
+        self.dir.with_extension("projects.json")
+    }
+
+    fn write_entry(&self, path: &Path, endpoint: &str, body: &str) -> Result<()> {
+        let entry = CacheEntry {
+            endpoint: endpoint.to_string(),
+            stored_at: now_secs(),
+            body: body.to_string(),
+        };
+        self.write_file(path, &serde_json::to_vec(&entry)?)
+    }
+
+    fn write_file(&self, path: &Path, data: &[u8]) -> Result<()> {
+        let dir = path.parent().unwrap_or(&self.dir);
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create cache directory: {}", dir.display()))?;
+
+        // Write to a temporary file first so readers never see partial entries
+        let tmp_path = path.with_extension(format!("tmp{}", std::process::id()));
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&tmp_path)?;
+        file.write_all(data)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// File name for an endpoint, e.g. `/project/abc/data` -> `project_abc_data.json`
+    fn entry_path(&self, endpoint: &str) -> PathBuf {
+        let name: String = endpoint
+            .trim_start_matches('/')
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        self.dir.join(format!("{}.json", name))
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(ttl_secs: u64) -> (tempfile::TempDir, ResponseCache) {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cache =
+            ResponseCache::new(temp_dir.path().join("cache"), Duration::from_secs(ttl_secs));
+        (temp_dir, cache)
+    }
+
+    #[test]
+    fn test_put_and_get() {
+        let (_dir, cache) = cache(60);
+        assert_eq!(cache.get("/project"), None);
+
+        cache.put("/project", r#"[{"id":"p1"}]"#);
+        assert_eq!(cache.get("/project").as_deref(), Some(r#"[{"id":"p1"}]"#));
+        assert_eq!(cache.get("/project/p1"), None);
+    }
+
+    #[test]
+    fn test_expired_entry_is_ignored() {
+        let (_dir, cache) = cache(0);
+        cache.put("/project", "[]");
+        assert_eq!(cache.get("/project"), None);
+    }
+
+    #[test]
+    fn test_invalidate_drops_all_entries() {
+        let (_dir, cache) = cache(60);
+        cache.put("/project", "[]");
+        cache.put("/project/p1/data", "{}");
+
+        cache.invalidate();
+        assert_eq!(cache.get("/project"), None);
+        assert_eq!(cache.get("/project/p1/data"), None);
+
+        // Invalidating an empty cache is fine
+        cache.invalidate();
+    }
+
+    fn work_project_names() -> ProjectNames {
+        use crate::api::project_names::ProjectName;
+
+        ProjectNames {
+            projects: vec![ProjectName {
+                id: "p1".to_string(),
+                name: "Work".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_project_names_survive_invalidation() {
+        let (_dir, cache) = cache(60);
+        assert_eq!(cache.project_names(), None);
+
+        let names = work_project_names();
+        cache.put_project_names(&names);
+        cache.invalidate();
+        assert_eq!(cache.project_names(), Some(names));
+        assert_eq!(cache.clone().with_bypass(true).project_names(), None);
+
+        cache.forget_project_names();
+        assert_eq!(cache.project_names(), None);
+    }
+
+    #[test]
+    fn test_expired_project_names_are_ignored() {
+        let (_dir, cache) = cache(0);
+        cache.put_project_names(&work_project_names());
+        assert_eq!(cache.project_names(), None);
+    }
+
+    #[test]
+    fn test_bypass_ignores_entries_but_still_writes() {
+        let (_dir, cache) = cache(60);
+        let bypassing = cache.clone().with_bypass(true);
+
+        bypassing.put("/project", "[]");
+        assert_eq!(bypassing.get("/project"), None);
+        assert_eq!(cache.get("/project").as_deref(), Some("[]"));
+    }
+
+    #[test]
+    fn test_entry_path_is_sanitized() {
+        let (_dir, cache) = cache(60);
+        assert_eq!(
+            cache.entry_path("/project/abc123/data"),
+            cache.dir().join("project_abc123_data.json")
+        );
+        assert_eq!(cache.entry_path("/../etc"), cache.dir().join("___etc.json"));
+    }
+
+    #[test]
+    fn test_accounts_use_separate_directories() {
+        let a = ResponseCache::for_account("https://a", "token", Duration::ZERO).unwrap();
+        let b = ResponseCache::for_account("https://a", "other", Duration::ZERO).unwrap();
+        assert_ne!(a.dir(), b.dir());
+        assert!(a.dir().starts_with(ResponseCache::root_dir().unwrap()));
+    }
+}
@@ -0,0 +1,473 @@
+//! Record/replay of API traffic ("cassettes")
+//!
+//! With `TICKRS_RECORD=<file>` every request and response going through
+//! [`TickTickClient`](crate::api::TickTickClient) is appended to a JSON
+//! cassette file. With `TICKRS_REPLAY=<file>` responses are served from such
+//! a file without touching the network, which makes it possible to reproduce
+//! a user's session or run end-to-end tests offline.
+//!
+//! The `Authorization` header is never written to disk.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use anyhow::{Context, Result};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+use reqwest::{StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::api::transport::{
+    HttpRequest, HttpResponse, Transport, TransportError, TransportErrorKind, TransportFuture,
+};
+
+/// Current cassette file format version
+const CASSETTE_VERSION: u32 = 1;
+
+/// Placeholder written instead of the bearer token
+const REDACTED: &str = "[REDACTED]";
+
+/// Cassettes being recorded by this process, by path. A command may create
+/// several clients; they all append to the same recording.
+static RECORDINGS: OnceLock<Mutex<HashMap<PathBuf, Arc<Mutex<Cassette>>>>> = OnceLock::new();
+
+/// A recorded session: requests in the order they were sent
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Cassette {
+    pub version: u32,
+    pub interactions: Vec<Interaction>,
+}
+
+/// A single request/response pair
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+/// A recorded request (with the bearer token redacted)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+}
+
+/// A recorded response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub body: String,
+}
+
+impl Cassette {
+    /// Load a cassette from a JSON file
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read cassette file: {}", path.display()))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse cassette file: {}", path.display()))
+    }
+
+    /// Write the cassette to a JSON file, replacing any existing content
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let contents =
+            serde_json::to_string_pretty(self).context("Failed to serialize cassette")?;
+        fs::write(path, contents)
+            .with_context(|| format!("Failed to write cassette file: {}", path.display()))
+    }
+}
+
+impl RecordedRequest {
+    fn from_request(request: &HttpRequest) -> Self {
+        let mut headers = header_map_to_strings(&request.headers);
+        if headers.contains_key(AUTHORIZATION.as_str()) {
+            headers.insert(
+                AUTHORIZATION.as_str().to_string(),
+                format!("Bearer {}", REDACTED),
+            );
+        }
+
+        Self {
+            method: request.method.to_string(),
+            url: request.url.clone(),
+            headers,
+            body: request
+                .body
+                .as_ref()
+                .map(|body| String::from_utf8_lossy(body).into_owned()),
+        }
+    }
+
+    /// Whether this recorded request matches a live one (method, path and query)
+    fn matches(&self, request: &HttpRequest) -> bool {
+        self.method.eq_ignore_ascii_case(request.method.as_str())
+            && path_and_query(&self.url) == path_and_query(&request.url)
+    }
+}
+
+impl RecordedResponse {
+    fn from_response(response: &HttpResponse) -> Self {
+        Self {
+            status: response.status.as_u16(),
+            headers: header_map_to_strings(&response.headers),
+            body: response.text(),
+        }
+    }
+
+    fn to_response(&self) -> Result<HttpResponse, TransportError> {
+        let status = StatusCode::from_u16(self.status).map_err(|e| {
+            TransportError::new(
+                TransportErrorKind::Other,
+                format!("Invalid cassette: {}", e),
+            )
+        })?;
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.insert(name, value);
+            }
+        }
+
+        Ok(HttpResponse {
+            status,
+            headers,
+            body: self.body.clone().into_bytes(),
+        })
+    }
+}
+
+/// Transport wrapper that writes every exchange to a cassette file
+pub struct RecordingTransport {
+    inner: Arc<dyn Transport>,
+    path: PathBuf,
+    cassette: Arc<Mutex<Cassette>>,
+}
+
+impl RecordingTransport {
+    /// Record all traffic sent through `inner` to the file at `path`.
+    ///
+    /// The file is created (or truncated) when the first recorder for `path`
+    /// in this process is created; later recorders append to it. It is
+    /// rewritten after every request, so the recording survives an aborted
+    /// process.
+    pub fn new(inner: Arc<dyn Transport>, path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut recordings = RECORDINGS
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let cassette = match recordings.get(&path) {
+            Some(cassette) => cassette.clone(),
+            None => {
+                let cassette = Cassette {
+                    version: CASSETTE_VERSION,
+                    interactions: Vec::new(),
+                };
+                cassette.save(&path)?;
+                let cassette = Arc::new(Mutex::new(cassette));
+                recordings.insert(path.clone(), cassette.clone());
+                cassette
+            }
+        };
+
+        Ok(Self {
+            inner,
+            path,
+            cassette,
+        })
+    }
+
+    fn record(&self, request: RecordedRequest, response: &HttpResponse) -> Result<()> {
+        let mut cassette = self.cassette.lock().unwrap_or_else(|e| e.into_inner());
+        cassette.interactions.push(Interaction {
+            request,
+            response: RecordedResponse::from_response(response),
+        });
+        cassette.save(&self.path)
+    }
+}
+
+impl Transport for RecordingTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let recorded = RecordedRequest::from_request(&request);
+            let response = self.inner.send(request).await?;
+
+            debug!("Recording {} {} to cassette", recorded.method, recorded.url);
+            self.record(recorded, &response)
+                .map_err(|e| TransportError::new(TransportErrorKind::Other, format!("{:#}", e)))?;
+
+            Ok(response)
+        })
+    }
+}
+
+/// Transport that answers requests from a cassette without any network access.
+///
+/// Interactions are served in recorded order: each request gets the first
+/// unused interaction with the same method, path and query. Once all matching
+/// interactions are used up, the last one is repeated.
+pub struct ReplayTransport {
+    path: PathBuf,
+    interactions: Vec<Interaction>,
+    used: Mutex<Vec<bool>>,
+}
+
+impl ReplayTransport {
+    /// Load a cassette file for replay
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let cassette = Cassette::load(&path)?;
+        Ok(Self::from_cassette(cassette, path))
+    }
+
+    /// Replay an in-memory cassette (`path` is only used in error messages)
+    pub fn from_cassette(cassette: Cassette, path: impl Into<PathBuf>) -> Self {
+        let used = vec![false; cassette.interactions.len()];
+        Self {
+            path: path.into(),
+            interactions: cassette.interactions,
+            used: Mutex::new(used),
+        }
+    }
+
+    fn find(&self, request: &HttpRequest) -> Option<&Interaction> {
+        let mut used = self.used.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut last_match = None;
+        for (index, interaction) in self.interactions.iter().enumerate() {
+            if !interaction.request.matches(request) {
+                continue;
+            }
+            if !used[index] {
+                used[index] = true;
+                return Some(interaction);
+            }
+            last_match = Some(interaction);
+        }
+        last_match
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        let result = match self.find(&request) {
+            Some(interaction) => {
+                debug!("Replaying {} {} from cassette", request.method, request.url);
+                interaction.response.to_response()
+            }
+            None => Err(TransportError::new(
+                TransportErrorKind::Other,
+                format!(
+                    "No recorded response for {} {} in cassette {}",
+                    request.method,
+                    path_and_query(&request.url),
+                    self.path.display()
+                ),
+            )),
+        };
+        Box::pin(async move { result })
+    }
+}
+
+/// Convert headers to a sorted string map, skipping non-UTF-8 values
+fn header_map_to_strings(headers: &HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|value| (name.as_str().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// The path and query of a URL, used to match requests independent of host
+fn path_and_query(url: &str) -> String {
+    match Url::parse(url) {
+        Ok(url) => match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        },
+        Err(_) => url.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::Method;
+    use std::collections::VecDeque;
+
+    /// Transport returning canned responses in order
+    struct Canned(Mutex<VecDeque<HttpResponse>>);
+
+    impl Transport for Canned {
+        fn send(&self, _request: HttpRequest) -> TransportFuture<'_> {
+            let response = self.0.lock().unwrap().pop_front().unwrap();
+            Box::pin(async move { Ok(response) })
+        }
+    }
+
+    fn request(method: Method, url: &str) -> HttpRequest {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_static("Bearer secret_token"),
+        );
+        HttpRequest {
+            method,
+            url: url.to_string(),
+            headers,
+            body: None,
+        }
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("tickrs_{}_{}.json", name, std::process::id()))
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let path = temp_path("cassette_roundtrip");
+        let mut response = HttpResponse::new(200, r#"[{"id":"p1","name":"Work"}]"#);
+        response
+            .headers
+            .insert("x-request-id", HeaderValue::from_static("abc"));
+        let inner = Arc::new(Canned(Mutex::new(VecDeque::from([response]))));
+
+        let recorder = RecordingTransport::new(inner, &path).unwrap();
+        recorder
+            .send(request(
+                Method::GET,
+                "https://api.ticktick.com/open/v1/project",
+            ))
+            .await
+            .unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(!contents.contains("secret_token"));
+        assert!(contents.contains(REDACTED));
+
+        // Replay works against a different host
+        let replay = ReplayTransport::load(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        let replayed = replay
+            .send(request(
+                Method::GET,
+                "http://127.0.0.1:9999/open/v1/project",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(replayed.status, StatusCode::OK);
+        assert_eq!(replayed.headers["x-request-id"], "abc");
+        assert_eq!(replayed.text(), r#"[{"id":"p1","name":"Work"}]"#);
+    }
+
+    #[tokio::test]
+    async fn test_recorders_for_same_path_append() {
+        let path = temp_path("cassette_append");
+        let canned = |body: &str| {
+            Arc::new(Canned(Mutex::new(VecDeque::from([HttpResponse::new(
+                200, body,
+            )]))))
+        };
+
+        let first = RecordingTransport::new(canned("[]"), &path).unwrap();
+        first
+            .send(request(
+                Method::GET,
+                "https://api.ticktick.com/open/v1/project",
+            ))
+            .await
+            .unwrap();
+        let second = RecordingTransport::new(canned("{}"), &path).unwrap();
+        second
+            .send(request(
+                Method::POST,
+                "https://api.ticktick.com/open/v1/task",
+            ))
+            .await
+            .unwrap();
+
+        let cassette = Cassette::load(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        let methods: Vec<&str> = cassette
+            .interactions
+            .iter()
+            .map(|i| i.request.method.as_str())
+            .collect();
+        assert_eq!(methods, vec!["GET", "POST"]);
+    }
+
+    #[tokio::test]
+    async fn test_replay_serves_interactions_in_order() {
+        let interaction = |status, body: &str| Interaction {
+            request: RecordedRequest {
+                method: "GET".to_string(),
+                url: "https://api.ticktick.com/open/v1/project".to_string(),
+                headers: BTreeMap::new(),
+                body: None,
+            },
+            response: RecordedResponse {
+                status,
+                headers: BTreeMap::new(),
+                body: body.to_string(),
+            },
+        };
+        let cassette = Cassette {
+            version: CASSETTE_VERSION,
+            interactions: vec![interaction(500, "oops"), interaction(200, "[]")],
+        };
+        let replay = ReplayTransport::from_cassette(cassette, "test.json");
+        let url = "https://api.ticktick.com/open/v1/project";
+
+        let first = replay.send(request(Method::GET, url)).await.unwrap();
+        let second = replay.send(request(Method::GET, url)).await.unwrap();
+        let third = replay.send(request(Method::GET, url)).await.unwrap();
+
+        assert_eq!(first.status.as_u16(), 500);
+        assert_eq!(second.status.as_u16(), 200);
+        // Last match is repeated once exhausted
+        assert_eq!(third.status.as_u16(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_replay_missing_interaction() {
+        let replay = ReplayTransport::from_cassette(Cassette::default(), "empty.json");
+        let err = replay
+            .send(request(
+                Method::DELETE,
+                "https://api.ticktick.com/open/v1/project/1",
+            ))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "No recorded response for DELETE /open/v1/project/1 in cassette empty.json"
+        );
+    }
+
+    #[test]
+    fn test_path_and_query() {
+        assert_eq!(
+            path_and_query("https://api.ticktick.com/open/v1/task?x=1"),
+            "/open/v1/task?x=1"
+        );
+        assert_eq!(path_and_query("not a url"), "not a url");
+    }
+}
@@ -1,19 +1,171 @@
-use anyhow::{Context, Result};
-use reqwest::{Client, Response, StatusCode};
+use std::env;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use futures_util::stream::{self, StreamExt};
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE, RETRY_AFTER};
+use reqwest::{Method, StatusCode};
 use serde::de::DeserializeOwned;
-use tracing::{debug, instrument};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{debug, instrument, warn};
 
-use crate::config::TokenStorage;
+use crate::api::auth::AuthHandler;
+use crate::api::batch::{RateLimiter, DEFAULT_MAX_IN_FLIGHT};
+use crate::api::cache::{self, ResponseCache};
+use crate::api::cassette::{RecordingTransport, ReplayTransport};
+use crate::api::http;
+use crate::api::project_names::ProjectNames;
+use crate::api::read_only::{self, ReadOnlyReason};
+use crate::api::refresh::TokenRefresher;
+use crate::api::retry::{parse_retry_after, RetryPolicy};
+use crate::api::timings;
+use crate::api::transport::{
+    HttpRequest, HttpResponse, ReqwestTransport, Transport, TransportError,
+};
+use crate::config::{Config, HttpConfig, TokenSet, TokenStorage};
+use crate::constants::{ENV_CLIENT_ID, ENV_CLIENT_SECRET, ENV_RECORD, ENV_REPLAY};
+use crate::utils::error::ErrorCode;
+use crate::utils::logging;
 
 /// Base URL for TickTick Open API
-pub const API_BASE_URL: &str = "https://api.ticktick.com/open/v1";
+pub use crate::constants::API_BASE_URL;
+
+/// Placeholder token used when replaying a cassette without being logged in
+const REPLAY_TOKEN: &str = "replay";
 
 /// TickTick API client wrapper
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct TickTickClient {
-    client: Client,
+    transport: Arc<dyn Transport>,
     token: String,
     base_url: String,
+    retry: RetryPolicy,
+    max_in_flight: usize,
+    rate_limiter: Arc<RateLimiter>,
+    cache: Option<ResponseCache>,
+    refresher: Option<Arc<TokenRefresher>>,
+    read_only: Option<ReadOnlyReason>,
+}
+
+impl fmt::Debug for TickTickClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TickTickClient")
+            .field("base_url", &self.base_url)
+            .field("retry", &self.retry)
+            .field("max_in_flight", &self.max_in_flight)
+            .field("cache", &self.cache)
+            .field("refreshes_token", &self.refresher.is_some())
+            .field("read_only", &self.read_only)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Structured details of a failed API request.
+///
+/// For error responses, TickTick usually sends a JSON body such as
+/// `{"errorCode": "task_not_found", "errorMessage": "..."}`. The upstream
+/// code is kept in `error_code` so callers can tell e.g. a missing task
+/// apart from a missing project.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ErrorInfo {
+    /// Human-readable message (TickTick's `errorMessage` or the raw body)
+    pub message: String,
+    /// Upstream error code (TickTick's `errorCode`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+    /// HTTP status code of the response
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    /// HTTP method of the request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    /// API endpoint of the request (e.g. "/project/abc/task/def")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+}
+
+/// Error body returned by the TickTick API
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UpstreamError {
+    error_code: Option<serde_json::Value>,
+    error_message: Option<String>,
+}
+
+impl ErrorInfo {
+    /// Build error details from an error response.
+    ///
+    /// Falls back to the raw body (or `fallback_message` if the body is
+    /// empty) when it isn't TickTick's error JSON.
+    fn from_response(
+        method: &Method,
+        endpoint: &str,
+        response: &HttpResponse,
+        fallback_message: &str,
+    ) -> Self {
+        let text = response.text();
+        let upstream = serde_json::from_str::<UpstreamError>(&text).ok();
+
+        let error_code = upstream
+            .as_ref()
+            .and_then(|e| e.error_code.as_ref())
+            .and_then(|code| match code {
+                serde_json::Value::String(code) => Some(code.clone()),
+                serde_json::Value::Null => None,
+                other => Some(other.to_string()),
+            })
+            .filter(|code| !code.is_empty());
+        let message = upstream
+            .and_then(|e| e.error_message)
+            .filter(|message| !message.is_empty())
+            .unwrap_or_else(|| {
+                if text.trim().is_empty() {
+                    fallback_message.to_string()
+                } else {
+                    text
+                }
+            });
+
+        Self {
+            message,
+            error_code,
+            status: Some(response.status.as_u16()),
+            method: Some(method.to_string()),
+            endpoint: Some(endpoint.to_string()),
+        }
+    }
+
+    /// Whether the upstream error code reports a missing resource
+    fn is_not_found(&self) -> bool {
+        self.error_code
+            .as_deref()
+            .is_some_and(|code| code.to_lowercase().ends_with("not_found"))
+    }
+}
+
+impl fmt::Display for ErrorInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl From<String> for ErrorInfo {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            ..Self::default()
+        }
+    }
+}
+
+impl From<&str> for ErrorInfo {
+    fn from(message: &str) -> Self {
+        Self::from(message.to_string())
+    }
 }
 
 /// API error response from TickTick
@@ -25,53 +177,316 @@ pub enum ApiError {
     #[error("Invalid or expired token. Run 'tickrs init' to re-authenticate.")]
     Unauthorized,
 
+    #[error("Failed to refresh the access token ({0}). Run 'tickrs init' to re-authenticate.")]
+    TokenRefreshFailed(String),
+
     #[error("Resource not found: {0}")]
-    NotFound(String),
+    NotFound(ErrorInfo),
 
     #[error("Bad request: {0}")]
-    BadRequest(String),
+    BadRequest(ErrorInfo),
 
     #[error("Rate limited. Please wait and try again.")]
-    RateLimited,
+    RateLimited {
+        /// Delay requested by the server via `Retry-After`
+        retry_after: Option<Duration>,
+    },
 
     #[error("Server error: {0}")]
-    ServerError(String),
+    ServerError(ErrorInfo),
 
     #[error("Network error: {0}")]
-    NetworkError(#[from] reqwest::Error),
+    NetworkError(#[from] TransportError),
 
     #[error("Failed to parse response: {0}")]
     ParseError(String),
+
+    #[error("Refusing to modify data: {0}")]
+    ReadOnly(ReadOnlyReason),
+
+    #[error("{source} (after {attempts} attempts)")]
+    Retried {
+        /// Total number of attempts made, including the first one
+        attempts: u32,
+        /// Error returned by the last attempt
+        source: Box<ApiError>,
+    },
+}
+
+impl ApiError {
+    /// Machine-readable error code for JSON output
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ApiError::NotAuthenticated => ErrorCode::AuthRequired,
+            ApiError::Unauthorized | ApiError::TokenRefreshFailed(_) => ErrorCode::AuthExpired,
+            ApiError::NotFound(_) => ErrorCode::NotFound,
+            ApiError::BadRequest(_) => ErrorCode::InvalidRequest,
+            ApiError::RateLimited { .. } => ErrorCode::RateLimited,
+            ApiError::ServerError(_) => ErrorCode::ServerError,
+            ApiError::NetworkError(_) => ErrorCode::NetworkError,
+            ApiError::ParseError(_) => ErrorCode::ParseError,
+            ApiError::ReadOnly(_) => ErrorCode::ReadOnly,
+            ApiError::Retried { source, .. } => source.code(),
+        }
+    }
+
+    /// Additional context for JSON error output, if any
+    pub fn details(&self) -> Option<serde_json::Value> {
+        match self {
+            ApiError::NotFound(info) | ApiError::BadRequest(info) | ApiError::ServerError(info) => {
+                let mut details = serde_json::to_value(info).ok()?;
+                let details_map = details.as_object_mut()?;
+                details_map.remove("message");
+                (!details_map.is_empty()).then_some(details)
+            }
+            ApiError::RateLimited {
+                retry_after: Some(retry_after),
+            } => Some(json!({ "retry_after_secs": retry_after.as_secs() })),
+            ApiError::Retried { attempts, source } => {
+                let mut details = source.details().unwrap_or_else(|| json!({}));
+                details["attempts"] = json!(attempts);
+                Some(details)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether retrying the request might succeed
+    fn is_transient(&self) -> bool {
+        match self {
+            ApiError::NetworkError(e) => e.is_timeout() || e.is_connect(),
+            _ => false,
+        }
+    }
+
+    /// Record how many attempts were made before this error was returned
+    fn after_attempts(self, attempts: u32) -> Self {
+        if attempts > 1 {
+            ApiError::Retried {
+                attempts,
+                source: Box::new(self),
+            }
+        } else {
+            self
+        }
+    }
 }
 
 impl TickTickClient {
-    /// Create a new client with the stored token
+    /// Create a new client with the stored token and configured settings
+    ///
+    /// Honors `TICKRS_RECORD` and `TICKRS_REPLAY` (see [`cassette`](crate::api::cassette)).
+    /// When replaying, no stored token is required. Responses are cached on
+    /// disk according to the `[cache]` config section, except while
+    /// recording or replaying.
+    ///
+    /// If the stored tokens include a refresh token, the access token is
+    /// refreshed automatically and the new tokens are saved.
     pub fn new() -> Result<Self> {
-        let token = TokenStorage::load()?.ok_or(ApiError::NotAuthenticated)?;
+        Self::from_config(&Config::load()?)
+    }
 
-        Self::with_token(token)
+    /// Like [`new()`](Self::new), with a config the caller already loaded
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let record = env::var_os(ENV_RECORD).filter(|v| !v.is_empty());
+        let replay = env::var_os(ENV_REPLAY).filter(|v| !v.is_empty());
+        if record.is_some() && replay.is_some() {
+            anyhow::bail!("{} and {} cannot be used together", ENV_RECORD, ENV_REPLAY);
+        }
+
+        let tokens = match TokenStorage::load_set()? {
+            Some(tokens) => tokens,
+            None if replay.is_some() => TokenSet::new(REPLAY_TOKEN),
+            None => return Err(ApiError::NotAuthenticated.into()),
+        };
+        logging::register_secret(&tokens.access_token);
+
+        let http_config = config.http.clone();
+        let endpoints = config.endpoints()?;
+
+        let cache = (config.cache.enabled && config.cache.ttl_secs > 0)
+            .then(|| {
+                ResponseCache::for_account(
+                    &endpoints.api_base_url,
+                    &tokens.access_token,
+                    Duration::from_secs(config.cache.ttl_secs),
+                )
+            })
+            .transpose()?;
+
+        let mut client = Self::build(
+            tokens.access_token.clone(),
+            endpoints.api_base_url.clone(),
+            &http_config,
+        )?
+        .with_retry_policy(RetryPolicy::from(&config.retry))
+        .with_max_in_flight(config.concurrency.max_in_flight)
+        .with_rate_limit(config.concurrency.requests_per_second)
+        .with_read_only(read_only::check(&tokens.scopes));
+
+        if let Some(cache) = cache.filter(|_| record.is_none() && replay.is_none()) {
+            client = client.with_cache(cache.with_bypass(cache::is_bypassed()));
+        }
+
+        if tokens.refresh_token.is_some() && replay.is_none() {
+            // Prefer the credentials the tokens were issued to
+            let client_id = tokens
+                .client_id
+                .clone()
+                .or_else(|| env::var(ENV_CLIENT_ID).ok());
+            let client_secret = tokens
+                .client_secret
+                .clone()
+                .or_else(|| env::var(ENV_CLIENT_SECRET).ok());
+            if let (Some(client_id), Some(client_secret)) = (client_id, client_secret) {
+                let auth = AuthHandler::new(client_id, client_secret)
+                    .with_endpoints(endpoints)
+                    .with_http_config(http_config);
+                let refresher = TokenRefresher::new(auth, tokens).with_persistence(true);
+                client = client.with_token_refresher(refresher);
+            } else {
+                debug!("No OAuth client credentials available, access token will not be refreshed");
+            }
+        }
+
+        if let Some(path) = replay {
+            return Ok(client.with_transport(ReplayTransport::load(path)?));
+        }
+        if let Some(path) = record {
+            let recorder = RecordingTransport::new(client.transport.clone(), path)?;
+            return Ok(client.with_transport(recorder));
+        }
+        Ok(client)
     }
 
     /// Create a new client with a specific token
+    #[allow(dead_code)] // Available for external use
     pub fn with_token(token: String) -> Result<Self> {
         Self::with_token_and_base_url(token, API_BASE_URL.to_string())
     }
 
     /// Create a new client with a specific token and base URL
     /// Primarily used for testing with mock servers
+    ///
+    /// Requests aren't rate limited unless [`with_rate_limit`](Self::with_rate_limit)
+    /// is called.
     pub fn with_token_and_base_url(token: String, base_url: String) -> Result<Self> {
-        let client = Client::builder()
-            .user_agent(format!("tickrs/{}", env!("CARGO_PKG_VERSION")))
-            .build()
-            .context("Failed to create HTTP client")?;
+        Self::build(token, base_url, &HttpConfig::default())
+    }
+
+    /// Create a client whose HTTP client uses the given timeouts, proxy and
+    /// root certificates
+    fn build(token: String, base_url: String, http_config: &HttpConfig) -> Result<Self> {
+        let client = http::build_client(http_config)?;
 
         Ok(Self {
-            client,
+            transport: Arc::new(ReqwestTransport::new(client)),
             token,
             base_url,
+            retry: RetryPolicy::default(),
+            max_in_flight: DEFAULT_MAX_IN_FLIGHT,
+            rate_limiter: Arc::new(RateLimiter::unlimited()),
+            cache: None,
+            refresher: None,
+            read_only: None,
         })
     }
 
+    /// Rebuild the underlying HTTP client with the given timeouts, proxy and
+    /// root certificates
+    #[allow(dead_code)] // Available for external use
+    pub fn with_http_config(self, config: &HttpConfig) -> Result<Self> {
+        let client = http::build_client(config)?;
+        Ok(self.with_transport(ReqwestTransport::new(client)))
+    }
+
+    /// Send all requests through the given transport instead of reqwest
+    pub fn with_transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    /// Replace the retry policy used for all requests
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Set the maximum number of requests [`batch`](Self::batch) keeps in flight (at least 1)
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = max_in_flight.max(1);
+        self
+    }
+
+    /// Limit the number of requests started per second (0 = unlimited).
+    ///
+    /// The budget is shared by this client and all of its clones.
+    pub fn with_rate_limit(mut self, requests_per_second: u32) -> Self {
+        self.rate_limiter = Arc::new(RateLimiter::new(requests_per_second));
+        self
+    }
+
+    /// Cache project lists and project data in the given cache.
+    ///
+    /// Any mutating request made through this client (or its clones)
+    /// invalidates the cache.
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Refresh the access token before it expires and when the server rejects it.
+    ///
+    /// The refresher's token replaces the client's token. Clones of this
+    /// client share the refresher, so the token is refreshed only once.
+    pub fn with_token_refresher(mut self, refresher: TokenRefresher) -> Self {
+        self.refresher = Some(Arc::new(refresher));
+        self
+    }
+
+    /// Refuse mutating requests, reporting `reason`, or allow them with `None`
+    pub fn with_read_only(mut self, reason: Option<ReadOnlyReason>) -> Self {
+        self.read_only = reason;
+        self
+    }
+
+    /// Run an async operation for every input with bounded parallelism.
+    ///
+    /// At most `max_in_flight` operations run at the same time, and results
+    /// are returned in the same order as the inputs, regardless of which
+    /// request finishes first.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use ticktickrs::api::TickTickClient;
+    /// # async fn example(client: TickTickClient) -> anyhow::Result<()> {
+    /// let projects = client.list_projects().await?;
+    /// let tasks = client
+    ///     .batch(&projects, |project| client.list_tasks(&project.id))
+    ///     .await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn batch<I, F, Fut, T>(&self, inputs: I, f: F) -> Vec<T>
+    where
+        I: IntoIterator,
+        F: FnMut(I::Item) -> Fut,
+        Fut: Future<Output = T>,
+    {
+        stream::iter(inputs)
+            .map(f)
+            .buffered(self.max_in_flight)
+            .collect()
+            .await
+    }
+
+    /// The retry policy used for all requests
+    #[allow(dead_code)] // Available for external use
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry
+    }
+
     /// Build the full URL for an endpoint
     fn url(&self, endpoint: &str) -> String {
         format!("{}{}", self.base_url, endpoint)
@@ -82,14 +497,57 @@ impl TickTickClient {
     pub async fn get<T: DeserializeOwned>(&self, endpoint: &str) -> Result<T, ApiError> {
         debug!("GET {}", endpoint);
 
-        let response = self
-            .client
-            .get(self.url(endpoint))
-            .bearer_auth(&self.token)
-            .send()
-            .await?;
+        let (response, attempts) = self.send(Method::GET, endpoint, None).await?;
+        self.handle_response(&Method::GET, endpoint, response)
+            .map_err(|e| e.after_attempts(attempts))
+    }
+
+    /// Make a GET request, serving the response from the cache if possible
+    #[instrument(skip(self), fields(endpoint = %endpoint))]
+    pub async fn get_cached<T: DeserializeOwned>(&self, endpoint: &str) -> Result<T, ApiError> {
+        let Some(cache) = &self.cache else {
+            return self.get(endpoint).await;
+        };
+
+        if let Some(body) = cache.get(endpoint) {
+            match serde_json::from_str(&body) {
+                Ok(value) => {
+                    timings::record_cache_hit();
+                    return Ok(value);
+                }
+                Err(e) => debug!("Ignoring unreadable cache entry for {}: {}", endpoint, e),
+            }
+        }
+
+        debug!("GET {}", endpoint);
+
+        let (response, attempts) = self.send(Method::GET, endpoint, None).await?;
+        let body = response.text();
+        let value = self
+            .handle_response(&Method::GET, endpoint, response)
+            .map_err(|e| e.after_attempts(attempts))?;
+        cache.put(endpoint, &body);
+        Ok(value)
+    }
 
-        self.handle_response(response).await
+    /// Name→ID map saved by the last project list, if responses are cached
+    /// and not bypassed with `--no-cache`
+    pub fn cached_project_names(&self) -> Option<ProjectNames> {
+        self.cache.as_ref()?.project_names()
+    }
+
+    /// Save the name→ID map of the account's projects, if responses are cached
+    pub(crate) fn cache_project_names(&self, names: &ProjectNames) {
+        if let Some(cache) = &self.cache {
+            cache.put_project_names(names);
+        }
+    }
+
+    /// Drop the cached name→ID map after projects were changed
+    pub(crate) fn forget_project_names(&self) {
+        if let Some(cache) = &self.cache {
+            cache.forget_project_names();
+        }
     }
 
     /// Make a POST request to the API with JSON body
@@ -101,15 +559,11 @@ impl TickTickClient {
     ) -> Result<T, ApiError> {
         debug!("POST {}", endpoint);
 
-        let response = self
-            .client
-            .post(self.url(endpoint))
-            .bearer_auth(&self.token)
-            .json(body)
-            .send()
-            .await?;
-
-        self.handle_response(response).await
+        let body = serde_json::to_vec(body)
+            .map_err(|e| ApiError::ParseError(format!("Failed to serialize request: {}", e)))?;
+        let (response, attempts) = self.send(Method::POST, endpoint, Some(body)).await?;
+        self.handle_response(&Method::POST, endpoint, response)
+            .map_err(|e| e.after_attempts(attempts))
     }
 
     /// Make a POST request without a body (for actions like complete)
@@ -117,14 +571,9 @@ impl TickTickClient {
     pub async fn post_empty<T: DeserializeOwned>(&self, endpoint: &str) -> Result<T, ApiError> {
         debug!("POST {} (empty body)", endpoint);
 
-        let response = self
-            .client
-            .post(self.url(endpoint))
-            .bearer_auth(&self.token)
-            .send()
-            .await?;
-
-        self.handle_response(response).await
+        let (response, attempts) = self.send(Method::POST, endpoint, None).await?;
+        self.handle_response(&Method::POST, endpoint, response)
+            .map_err(|e| e.after_attempts(attempts))
     }
 
     /// Make a DELETE request to the API
@@ -132,85 +581,318 @@ impl TickTickClient {
     pub async fn delete(&self, endpoint: &str) -> Result<(), ApiError> {
         debug!("DELETE {}", endpoint);
 
-        let response = self
-            .client
-            .delete(self.url(endpoint))
-            .bearer_auth(&self.token)
-            .send()
-            .await?;
+        let (response, attempts) = self.send(Method::DELETE, endpoint, None).await?;
+        self.handle_empty_response(&Method::DELETE, endpoint, response)
+            .map_err(|e| e.after_attempts(attempts))
+    }
+
+    /// Send a request, recording its timing and invalidating the response
+    /// cache after any mutation.
+    ///
+    /// The cache is invalidated even if the request failed, since the server
+    /// may have applied the change anyway (e.g. after a timeout). Mutations
+    /// of a read-only client fail without sending anything.
+    async fn send(
+        &self,
+        method: Method,
+        endpoint: &str,
+        body: Option<Vec<u8>>,
+    ) -> Result<(HttpResponse, u32), ApiError> {
+        if method != Method::GET {
+            if let Some(reason) = self.read_only {
+                return Err(ApiError::ReadOnly(reason));
+            }
+        }
+
+        let started = Instant::now();
+        let result = self.send_with_retries(method.clone(), endpoint, body).await;
+        let (status, attempts) = match &result {
+            Ok((response, attempts)) => (Some(response.status.as_u16()), *attempts),
+            Err(ApiError::Retried { attempts, .. }) => (None, *attempts),
+            Err(_) => (None, 1),
+        };
+        timings::record_request(
+            method.as_str(),
+            endpoint,
+            status,
+            started.elapsed(),
+            attempts,
+        );
+
+        if method != Method::GET {
+            if let Some(cache) = &self.cache {
+                cache.invalidate();
+            }
+        }
+        result
+    }
+
+    /// Send a request, retrying transient failures according to the retry policy.
+    ///
+    /// Returns the final response together with the number of attempts made.
+    /// Rate-limited and server error responses are returned as-is once the
+    /// policy is exhausted, so the usual response handling maps them to errors.
+    /// A `401 Unauthorized` response is retried once after refreshing the
+    /// access token, if the client has a [`TokenRefresher`].
+    async fn send_with_retries(
+        &self,
+        method: Method,
+        endpoint: &str,
+        body: Option<Vec<u8>>,
+    ) -> Result<(HttpResponse, u32), ApiError> {
+        let mut token = match &self.refresher {
+            Some(refresher) => refresher.access_token().await?,
+            None => self.token.clone(),
+        };
+        let mut request = self.build_request(method.clone(), endpoint, body, &token)?;
+        let mut refreshed = false;
+        let mut attempt = 1;
+
+        loop {
+            self.rate_limiter.acquire().await;
+            let started = Instant::now();
+            let result = self.transport.send(request.clone()).await;
+            let elapsed = started.elapsed();
+            let (reason, retry_after) = match result {
+                Ok(response) => {
+                    let status = response.status;
+                    if status == StatusCode::TOO_MANY_REQUESTS {
+                        timings::record_rate_limited();
+                    }
+                    debug!(
+                        "{} {} -> {} in {}ms",
+                        method,
+                        request.url,
+                        status,
+                        elapsed.as_millis()
+                    );
+                    if status == StatusCode::UNAUTHORIZED && !refreshed {
+                        if let Some(refresher) = &self.refresher {
+                            debug!("Access token rejected, refreshing");
+                            token = refresher.refresh(&token).await?;
+                            request.headers.insert(AUTHORIZATION, bearer(&token)?);
+                            refreshed = true;
+                            continue;
+                        }
+                    }
+                    if status != StatusCode::TOO_MANY_REQUESTS && !status.is_server_error() {
+                        return Ok((response, attempt));
+                    }
+                    let retry_after = header_retry_after(&response.headers);
+
+                    match self.retry_delay(&method, attempt, retry_after) {
+                        Some(delay) => (status.to_string(), delay),
+                        None => return Ok((response, attempt)),
+                    }
+                }
+                Err(e) => {
+                    debug!(
+                        "{} {} failed after {}ms: {}",
+                        method,
+                        request.url,
+                        elapsed.as_millis(),
+                        e
+                    );
+                    let err = ApiError::from(e);
+                    if !err.is_transient() {
+                        return Err(err.after_attempts(attempt));
+                    }
+                    match self.retry_delay(&method, attempt, None) {
+                        Some(delay) => (err.to_string(), delay),
+                        None => return Err(err.after_attempts(attempt)),
+                    }
+                }
+            };
+
+            warn!(
+                "{} {} failed ({}), retrying in {}ms (attempt {}/{})",
+                method,
+                endpoint,
+                reason,
+                retry_after.as_millis(),
+                attempt + 1,
+                self.retry.max_attempts
+            );
+            tokio::time::sleep(retry_after).await;
+            attempt += 1;
+        }
+    }
+
+    /// Build an authenticated request for an endpoint
+    fn build_request(
+        &self,
+        method: Method,
+        endpoint: &str,
+        body: Option<Vec<u8>>,
+        token: &str,
+    ) -> Result<HttpRequest, ApiError> {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, bearer(token)?);
+        if body.is_some() {
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        }
+
+        Ok(HttpRequest {
+            method,
+            url: self.url(endpoint),
+            headers,
+            body,
+        })
+    }
 
-        self.handle_empty_response(response).await
+    /// Delay before the next attempt, or `None` if the request should not be retried
+    fn retry_delay(
+        &self,
+        method: &Method,
+        attempt: u32,
+        retry_after: Option<Duration>,
+    ) -> Option<Duration> {
+        if !self.retry.allows_retry(method, attempt) {
+            return None;
+        }
+        let delay = self.retry.delay(attempt, retry_after);
+        if delay.is_none() {
+            warn!(
+                "Server asked to retry after {:?}, which exceeds the maximum delay of {:?}",
+                retry_after.unwrap_or_default(),
+                self.retry.max_delay
+            );
+        }
+        delay
     }
 
     /// Handle API response and parse JSON
-    async fn handle_response<T: DeserializeOwned>(
+    fn handle_response<T: DeserializeOwned>(
         &self,
-        response: Response,
+        method: &Method,
+        endpoint: &str,
+        response: HttpResponse,
     ) -> Result<T, ApiError> {
-        let status = response.status();
-        let url = response.url().to_string();
-
-        match status {
+        match response.status {
             StatusCode::OK | StatusCode::CREATED => {
-                let text = response.text().await?;
+                let text = response.text();
                 debug!("Response: {}", &text[..text.len().min(500)]);
                 serde_json::from_str(&text).map_err(|e| {
                     ApiError::ParseError(format!("{}: {}", e, &text[..text.len().min(200)]))
                 })
             }
-            StatusCode::UNAUTHORIZED => Err(ApiError::Unauthorized),
-            StatusCode::NOT_FOUND => Err(ApiError::NotFound(url)),
-            StatusCode::BAD_REQUEST => {
-                let text = response.text().await.unwrap_or_default();
-                Err(ApiError::BadRequest(text))
-            }
-            StatusCode::TOO_MANY_REQUESTS => Err(ApiError::RateLimited),
-            _ if status.is_server_error() => {
-                let text = response.text().await.unwrap_or_default();
-                Err(ApiError::ServerError(format!("{}: {}", status, text)))
-            }
-            _ => {
-                let text = response.text().await.unwrap_or_default();
-                Err(ApiError::ServerError(format!(
-                    "Unexpected status {}: {}",
-                    status, text
-                )))
-            }
+            _ => Err(self.error_from_response(method, endpoint, &response)),
         }
     }
 
     /// Handle API response for endpoints that return empty body
-    async fn handle_empty_response(&self, response: Response) -> Result<(), ApiError> {
-        let status = response.status();
-        let url = response.url().to_string();
+    fn handle_empty_response(
+        &self,
+        method: &Method,
+        endpoint: &str,
+        response: HttpResponse,
+    ) -> Result<(), ApiError> {
+        match response.status {
+            StatusCode::OK | StatusCode::NO_CONTENT => Ok(()),
+            _ => Err(self.error_from_response(method, endpoint, &response)),
+        }
+    }
 
+    /// Map an unsuccessful response to an error.
+    ///
+    /// Responses whose upstream error code ends in `not_found` are reported
+    /// as [`ApiError::NotFound`] regardless of the HTTP status, since
+    /// TickTick reports some missing resources with a 500.
+    fn error_from_response(
+        &self,
+        method: &Method,
+        endpoint: &str,
+        response: &HttpResponse,
+    ) -> ApiError {
+        let status = response.status;
         match status {
-            StatusCode::OK | StatusCode::NO_CONTENT => Ok(()),
-            StatusCode::UNAUTHORIZED => Err(ApiError::Unauthorized),
-            StatusCode::NOT_FOUND => Err(ApiError::NotFound(url)),
-            StatusCode::BAD_REQUEST => {
-                let text = response.text().await.unwrap_or_default();
-                Err(ApiError::BadRequest(text))
-            }
-            StatusCode::TOO_MANY_REQUESTS => Err(ApiError::RateLimited),
-            _ if status.is_server_error() => {
-                let text = response.text().await.unwrap_or_default();
-                Err(ApiError::ServerError(format!("{}: {}", status, text)))
-            }
-            _ => {
-                let text = response.text().await.unwrap_or_default();
-                Err(ApiError::ServerError(format!(
-                    "Unexpected status {}: {}",
-                    status, text
-                )))
-            }
+            StatusCode::UNAUTHORIZED => return ApiError::Unauthorized,
+            StatusCode::TOO_MANY_REQUESTS => return rate_limited(response),
+            _ => {}
+        }
+
+        let url = self.url(endpoint);
+        let mut info = ErrorInfo::from_response(method, endpoint, response, &url);
+        if status == StatusCode::NOT_FOUND || info.is_not_found() {
+            return ApiError::NotFound(info);
+        }
+        if status == StatusCode::BAD_REQUEST {
+            return ApiError::BadRequest(info);
         }
+
+        info.message = if status.is_server_error() {
+            format!("{}: {}", status, info.message)
+        } else {
+            format!("Unexpected status {}: {}", status, info.message)
+        };
+        ApiError::ServerError(info)
+    }
+}
+
+/// Build a rate limit error from a 429 response
+fn rate_limited(response: &HttpResponse) -> ApiError {
+    ApiError::RateLimited {
+        retry_after: header_retry_after(&response.headers),
     }
 }
 
+/// `Authorization` header value for a bearer token
+fn bearer(token: &str) -> Result<HeaderValue, ApiError> {
+    HeaderValue::from_str(&format!("Bearer {}", token)).map_err(|_| ApiError::Unauthorized)
+}
+
+/// Parse the `Retry-After` header, if present
+fn header_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(parse_retry_after)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::api::transport::{TransportErrorKind, TransportFuture};
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    /// In-memory transport returning canned responses and recording requests
+    #[derive(Clone, Default)]
+    struct FakeTransport {
+        responses: Arc<Mutex<VecDeque<Result<HttpResponse, TransportError>>>>,
+        requests: Arc<Mutex<Vec<HttpRequest>>>,
+    }
+
+    impl FakeTransport {
+        fn respond(self, result: Result<HttpResponse, TransportError>) -> Self {
+            self.responses.lock().unwrap().push_back(result);
+            self
+        }
+
+        fn requests(&self) -> Vec<HttpRequest> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    impl Transport for FakeTransport {
+        fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+            self.requests.lock().unwrap().push(request);
+            let response = self
+                .responses
+                .lock()
+                .unwrap()
+                .pop_front()
+                .expect("no response left");
+            Box::pin(async move { response })
+        }
+    }
+
+    fn fake_client(transport: &FakeTransport) -> TickTickClient {
+        TickTickClient::with_token_and_base_url("test_token".to_string(), "http://fake".to_string())
+            .unwrap()
+            .with_retry_policy(RetryPolicy::default().with_base_delay(Duration::from_millis(1)))
+            .with_transport(transport.clone())
+    }
 
     #[test]
     fn test_url_building() {
@@ -238,8 +920,183 @@ mod tests {
             "Invalid or expired token. Run 'tickrs init' to re-authenticate."
         );
         assert_eq!(
-            ApiError::NotFound("/project/123".to_string()).to_string(),
+            ApiError::NotFound("/project/123".into()).to_string(),
             "Resource not found: /project/123"
         );
     }
+
+    #[test]
+    fn test_retried_error_display_and_details() {
+        let err = ApiError::Retried {
+            attempts: 3,
+            source: Box::new(ApiError::RateLimited {
+                retry_after: Some(Duration::from_secs(2)),
+            }),
+        };
+
+        assert_eq!(
+            err.to_string(),
+            "Rate limited. Please wait and try again. (after 3 attempts)"
+        );
+        assert_eq!(err.code(), ErrorCode::RateLimited);
+
+        let details = err.details().unwrap();
+        assert_eq!(details["attempts"], 3);
+        assert_eq!(details["retry_after_secs"], 2);
+    }
+
+    #[test]
+    fn test_after_attempts_only_wraps_retried_requests() {
+        let err = ApiError::Unauthorized.after_attempts(1);
+        assert!(matches!(err, ApiError::Unauthorized));
+
+        let err = ApiError::Unauthorized.after_attempts(2);
+        assert!(matches!(err, ApiError::Retried { attempts: 2, .. }));
+    }
+
+    #[test]
+    fn test_default_retry_policy() {
+        let client = TickTickClient::with_token("test_token".to_string()).unwrap();
+        assert_eq!(client.retry_policy(), &RetryPolicy::default());
+
+        let client = client.with_retry_policy(RetryPolicy::none());
+        assert_eq!(client.retry_policy().max_attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_fake_transport_receives_authenticated_request() {
+        let transport = FakeTransport::default().respond(Ok(HttpResponse::new(
+            200,
+            r#"{"id": "proj1", "name": "Work"}"#,
+        )));
+        let client = fake_client(&transport);
+
+        let project: serde_json::Value = client
+            .post("/project", &json!({ "name": "Work" }))
+            .await
+            .unwrap();
+        assert_eq!(project["id"], "proj1");
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, Method::POST);
+        assert_eq!(requests[0].url, "http://fake/project");
+        assert_eq!(requests[0].headers[AUTHORIZATION], "Bearer test_token");
+        assert_eq!(requests[0].headers[CONTENT_TYPE], "application/json");
+        assert_eq!(
+            requests[0].body.as_deref(),
+            Some(br#"{"name":"Work"}"#.as_ref())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_only_client_refuses_mutations_without_sending() {
+        let transport = FakeTransport::default().respond(Ok(HttpResponse::new(200, "[]")));
+        let client = fake_client(&transport).with_read_only(Some(ReadOnlyReason::TokenScopes));
+
+        let projects: serde_json::Value = client.get("/project").await.unwrap();
+        assert_eq!(projects, json!([]));
+
+        let err = client
+            .post::<serde_json::Value, _>("/task", &json!({ "title": "x" }))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ApiError::ReadOnly(ReadOnlyReason::TokenScopes)
+        ));
+        assert_eq!(err.code(), ErrorCode::ReadOnly);
+        assert!(client.delete("/task/1").await.is_err());
+        assert!(client
+            .post_empty::<serde_json::Value>("/project/p/task/1/complete")
+            .await
+            .is_err());
+
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_fake_transport_error_mapping() {
+        let transport = FakeTransport::default()
+            .respond(Ok(HttpResponse::new(401, "")))
+            .respond(Ok(HttpResponse::new(404, "")))
+            .respond(Ok(HttpResponse::new(400, "bad title")));
+        let client = fake_client(&transport);
+
+        let err = client.get::<serde_json::Value>("/task").await.unwrap_err();
+        assert!(matches!(err, ApiError::Unauthorized));
+
+        let err = client.delete("/task/1").await.unwrap_err();
+        assert_eq!(err.to_string(), "Resource not found: http://fake/task/1");
+
+        let err = client.get::<serde_json::Value>("/task").await.unwrap_err();
+        assert_eq!(err.to_string(), "Bad request: bad title");
+    }
+
+    #[tokio::test]
+    async fn test_transient_transport_error_is_retried() {
+        let transport = FakeTransport::default()
+            .respond(Err(TransportError::new(
+                TransportErrorKind::Timeout,
+                "timed out",
+            )))
+            .respond(Ok(HttpResponse::new(204, "")));
+        let client = fake_client(&transport);
+
+        client.delete("/project/1").await.unwrap();
+        assert_eq!(transport.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_other_transport_error_is_not_retried() {
+        let transport = FakeTransport::default().respond(Err(TransportError::new(
+            TransportErrorKind::Other,
+            "invalid certificate",
+        )));
+        let client = fake_client(&transport);
+
+        let err = client.delete("/project/1").await.unwrap_err();
+        assert_eq!(err.to_string(), "Network error: invalid certificate");
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    /// Transport that tracks how many requests are in flight at once
+    #[derive(Default)]
+    struct ConcurrencyProbe {
+        in_flight: std::sync::atomic::AtomicUsize,
+        max_seen: std::sync::atomic::AtomicUsize,
+    }
+
+    impl Transport for Arc<ConcurrencyProbe> {
+        fn send(&self, _request: HttpRequest) -> TransportFuture<'_> {
+            use std::sync::atomic::Ordering;
+            Box::pin(async move {
+                let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                self.max_seen.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                self.in_flight.fetch_sub(1, Ordering::SeqCst);
+                Ok(HttpResponse::new(204, ""))
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_limits_requests_in_flight() {
+        let probe = Arc::new(ConcurrencyProbe::default());
+        let client = TickTickClient::with_token("test_token".to_string())
+            .unwrap()
+            .with_transport(probe.clone())
+            .with_max_in_flight(3);
+
+        let results = client
+            .batch(0..10, |i| {
+                let client = &client;
+                async move { client.delete(&format!("/project/{}", i)).await }
+            })
+            .await;
+
+        assert_eq!(results.len(), 10);
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(probe.max_seen.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
 }
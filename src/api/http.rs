@@ -0,0 +1,118 @@
+//! Shared HTTP client construction
+//!
+//! Both the API client and the OAuth token exchange build their
+//! `reqwest::Client` here so timeouts, proxy and custom root certificates
+//! from [`HttpConfig`] apply to every request tickrs makes.
+
+use std::fs;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use reqwest::{Certificate, ClientBuilder, Proxy};
+
+use crate::config::HttpConfig;
+
+/// User agent sent with every request
+pub fn user_agent() -> String {
+    format!("tickrs/{}", env!("CARGO_PKG_VERSION"))
+}
+
+/// Create a client builder configured from the given HTTP settings.
+///
+/// Callers can add further options (such as a redirect policy) before
+/// building the client.
+pub fn client_builder(config: &HttpConfig) -> Result<ClientBuilder> {
+    let mut builder = reqwest::Client::builder().user_agent(user_agent());
+
+    if config.connect_timeout_secs > 0 {
+        builder = builder.connect_timeout(Duration::from_secs(config.connect_timeout_secs));
+    }
+    if config.read_timeout_secs > 0 {
+        builder = builder.read_timeout(Duration::from_secs(config.read_timeout_secs));
+    }
+
+    if let Some(proxy_url) = &config.https_proxy {
+        let proxy = Proxy::https(proxy_url)
+            .with_context(|| format!("Invalid HTTPS proxy URL: {}", proxy_url))?;
+        builder = builder.proxy(proxy);
+    }
+
+    for path in &config.ca_certs {
+        let pem = fs::read(path)
+            .with_context(|| format!("Failed to read CA certificate file: {}", path.display()))?;
+        let certs = Certificate::from_pem_bundle(&pem)
+            .with_context(|| format!("Invalid PEM in CA certificate file: {}", path.display()))?;
+        if certs.is_empty() {
+            anyhow::bail!("No certificates found in CA file: {}", path.display());
+        }
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    Ok(builder)
+}
+
+/// Build a client from the given HTTP settings
+pub fn build_client(config: &HttpConfig) -> Result<reqwest::Client> {
+    client_builder(config)?
+        .build()
+        .context("Failed to create HTTP client")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_build_client_with_defaults() {
+        assert!(build_client(&HttpConfig::default()).is_ok());
+    }
+
+    #[test]
+    fn test_build_client_with_proxy() {
+        let config = HttpConfig {
+            https_proxy: Some("http://proxy.example.com:3128".to_string()),
+            ..HttpConfig::default()
+        };
+        assert!(build_client(&config).is_ok());
+    }
+
+    #[test]
+    fn test_build_client_invalid_proxy() {
+        let config = HttpConfig {
+            https_proxy: Some("not a url".to_string()),
+            ..HttpConfig::default()
+        };
+        let err = build_client(&config).unwrap_err();
+        assert!(err.to_string().contains("Invalid HTTPS proxy URL"));
+    }
+
+    #[test]
+    fn test_build_client_missing_ca_file() {
+        let config = HttpConfig {
+            ca_certs: vec![PathBuf::from("/nonexistent/tickrs-ca.pem")],
+            ..HttpConfig::default()
+        };
+        let err = build_client(&config).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Failed to read CA certificate file"));
+    }
+
+    #[test]
+    fn test_build_client_ca_file_without_certificates() {
+        let path = std::env::temp_dir().join(format!("tickrs_ca_test_{}.pem", std::process::id()));
+        fs::write(&path, "not a certificate\n").unwrap();
+
+        let config = HttpConfig {
+            ca_certs: vec![path.clone()],
+            ..HttpConfig::default()
+        };
+        let result = build_client(&config);
+        let _ = fs::remove_file(&path);
+
+        assert!(result.is_err());
+    }
+}
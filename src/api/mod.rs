@@ -7,7 +7,12 @@
 //!
 //! - [`TickTickClient`] - The main API client for making authenticated requests
 //! - [`AuthHandler`] - Handles OAuth 2.0 authentication flow
+//! - [`TokenRefresher`] - Refreshes expired access tokens automatically
 //! - [`ApiError`] - Error types returned by API operations
+//! - [`RetryPolicy`] - Backoff and retry settings for transient failures
+//! - [`Transport`] - Pluggable HTTP layer (reqwest by default, or an in-memory fake)
+//! - [`ResponseCache`] - On-disk cache for project lists and project data
+//! - [`ProjectNames`] - Resolves project names to IDs, by prefix or fuzzy match
 //!
 //! # Request Types
 //!
@@ -39,12 +44,34 @@
 //! ```
 
 pub mod auth;
+pub mod batch;
+pub mod cache;
+pub mod cassette;
 pub mod client;
+pub mod http;
 pub mod project;
+pub mod project_names;
+pub mod read_only;
+pub mod refresh;
+pub mod retry;
 pub mod task;
+pub mod timings;
+pub mod transport;
 pub mod types;
 
 pub use auth::AuthHandler;
+#[allow(unused_imports)] // Re-exported for library consumers
+pub use cache::ResponseCache;
+#[allow(unused_imports)] // Re-exported for library consumers
+pub use client::ErrorInfo;
 pub use client::{ApiError, TickTickClient};
 pub use project::{CreateProjectRequest, UpdateProjectRequest};
+#[allow(unused_imports)] // Re-exported for library consumers
+pub use project_names::{ProjectNameError, ProjectNames};
+#[allow(unused_imports)] // Re-exported for library consumers
+pub use refresh::TokenRefresher;
+#[allow(unused_imports)] // Re-exported for library consumers
+pub use retry::RetryPolicy;
 pub use task::{CreateTaskRequest, UpdateTaskRequest};
+#[allow(unused_imports)] // Re-exported for library consumers
+pub use transport::{HttpRequest, HttpResponse, Transport};
@@ -1,6 +1,7 @@
 //! Project API endpoints for TickTick
 
 use crate::api::client::{ApiError, TickTickClient};
+use crate::api::project_names::ProjectNames;
 use crate::models::{Project, ProjectData, INBOX_PROJECT_ID};
 use tracing::{debug, instrument};
 
@@ -87,10 +88,11 @@ impl TickTickClient {
     pub async fn list_projects(&self) -> Result<Vec<Project>, ApiError> {
         debug!("Listing all projects");
 
-        let mut projects: Vec<Project> = self.get("/project").await?;
+        let mut projects: Vec<Project> = self.get_cached("/project").await?;
 
         // Add INBOX project at the beginning (it's not returned by the API)
         projects.insert(0, Project::inbox());
+        self.cache_project_names(&ProjectNames::from_projects(&projects));
 
         debug!("Found {} projects (including inbox)", projects.len());
         Ok(projects)
@@ -109,7 +111,7 @@ impl TickTickClient {
         }
 
         let endpoint = format!("/project/{}", id);
-        self.get(&endpoint).await
+        self.get_cached(&endpoint).await
     }
 
     /// Get project with its tasks and columns
@@ -120,7 +122,7 @@ impl TickTickClient {
         debug!("Getting project data: {}", id);
 
         let endpoint = format!("/project/{}/data", id);
-        self.get(&endpoint).await
+        self.get_cached(&endpoint).await
     }
 
     /// Create a new project
@@ -133,7 +135,9 @@ impl TickTickClient {
     ) -> Result<Project, ApiError> {
         debug!("Creating project: {}", request.name);
 
-        self.post("/project", request).await
+        let project = self.post("/project", request).await?;
+        self.forget_project_names();
+        Ok(project)
     }
 
     /// Update an existing project
@@ -148,13 +152,13 @@ impl TickTickClient {
         debug!("Updating project: {}", id);
 
         if id == INBOX_PROJECT_ID {
-            return Err(ApiError::BadRequest(
-                "Cannot update INBOX project".to_string(),
-            ));
+            return Err(ApiError::BadRequest("Cannot update INBOX project".into()));
         }
 
         let endpoint = format!("/project/{}", id);
-        self.post(&endpoint, request).await
+        let project = self.post(&endpoint, request).await?;
+        self.forget_project_names();
+        Ok(project)
     }
 
     /// Delete a project
@@ -165,13 +169,13 @@ impl TickTickClient {
         debug!("Deleting project: {}", id);
 
         if id == INBOX_PROJECT_ID {
-            return Err(ApiError::BadRequest(
-                "Cannot delete INBOX project".to_string(),
-            ));
+            return Err(ApiError::BadRequest("Cannot delete INBOX project".into()));
         }
 
         let endpoint = format!("/project/{}", id);
-        self.delete(&endpoint).await
+        self.delete(&endpoint).await?;
+        self.forget_project_names();
+        Ok(())
     }
 }
 
@@ -0,0 +1,480 @@
+//! Resolving project names given on the command line
+//!
+//! A name given with `--project-name` is looked up in this order:
+//!
+//! 1. An alias from the `[project_alias]` config section, replaced by its
+//!    project name or ID
+//! 2. A project ID, or `inbox` for the Inbox
+//! 3. The exact name, ignoring case
+//! 4. A prefix of one name
+//! 5. A fuzzy match: the only name containing the query, containing its
+//!    letters in order, or differing from it by a typo
+//!
+//! When a step matches several projects, resolution stops with
+//! [`ProjectNameError::Ambiguous`] listing them. Commands that modify data
+//! resolve names in [`MatchMode::Strict`], which skips step 5: a fuzzy match
+//! fails with [`ProjectNameError::Inexact`] listing the projects it found.
+//! Matches from steps 4 and 5 are recorded by
+//! [`ProjectNames::resolve_recorded()`] so the CLI can tell which project it
+//! picked; see [`inexact_matches()`].
+//!
+//! [`ProjectNames`] maps the account's project names to IDs. The client
+//! saves it whenever it lists projects, so names it resolves exactly (steps
+//! 1 to 3) don't need a request; see
+//! [`TickTickClient::cached_project_names()`](crate::api::TickTickClient::cached_project_names).
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use thiserror::Error;
+
+use crate::models::{Project, INBOX_PROJECT_ID};
+use crate::utils::error::ErrorCode;
+
+/// Process-wide list of names resolved by prefix or fuzzy match
+static INEXACT_MATCHES: Mutex<Vec<InexactMatch>> = Mutex::new(Vec::new());
+
+/// A project name resolved by prefix or fuzzy match
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InexactMatch {
+    /// The name as given
+    pub query: String,
+    /// ID of the chosen project
+    pub id: String,
+    /// Name of the chosen project
+    pub name: String,
+}
+
+/// Names this process resolved by prefix or fuzzy match, in order
+pub fn inexact_matches() -> Vec<InexactMatch> {
+    INEXACT_MATCHES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Name and ID of a project
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectName {
+    pub id: String,
+    pub name: String,
+}
+
+/// Errors resolving a project name
+#[derive(Debug, Error)]
+pub enum ProjectNameError {
+    #[error("Project not found: {0}")]
+    NotFound(String),
+
+    #[error(
+        "Project name '{name}' is ambiguous, it matches {}. Use more of the name or --project-id",
+        format_candidates(candidates)
+    )]
+    Ambiguous {
+        name: String,
+        candidates: Vec<ProjectName>,
+    },
+
+    #[error(
+        "Project name '{name}' only matches {} approximately. Use the exact name, its start or --project-id",
+        format_candidates(candidates)
+    )]
+    Inexact {
+        name: String,
+        candidates: Vec<ProjectName>,
+    },
+}
+
+/// How loosely a project name may match
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchMode {
+    /// ID, `inbox`, exact name or unique prefix, for commands that modify data
+    Strict,
+    /// Also substring, letters-in-order and typo matches
+    Fuzzy,
+}
+
+impl ProjectNameError {
+    /// Machine-readable error code for JSON output
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ProjectNameError::NotFound(_) => ErrorCode::NotFound,
+            ProjectNameError::Ambiguous { .. } | ProjectNameError::Inexact { .. } => {
+                ErrorCode::InvalidRequest
+            }
+        }
+    }
+
+    /// The matching projects for JSON error output, if ambiguous
+    pub fn details(&self) -> Option<serde_json::Value> {
+        match self {
+            ProjectNameError::Ambiguous { candidates, .. }
+            | ProjectNameError::Inexact { candidates, .. } => {
+                Some(json!({ "candidates": candidates }))
+            }
+            ProjectNameError::NotFound(_) => None,
+        }
+    }
+}
+
+fn format_candidates(candidates: &[ProjectName]) -> String {
+    candidates
+        .iter()
+        .map(|p| format!("'{}' ({})", p.name, p.id))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Name→ID map of an account's projects
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectNames {
+    pub projects: Vec<ProjectName>,
+}
+
+impl ProjectNames {
+    /// Map of the given projects
+    pub fn from_projects(projects: &[Project]) -> Self {
+        Self {
+            projects: projects
+                .iter()
+                .map(|p| ProjectName {
+                    id: p.id.clone(),
+                    name: p.name.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Find a project by ID, `inbox` or exact name (ignoring case).
+    ///
+    /// Returns `None` if nothing or more than one project matches.
+    pub fn find_exact(&self, query: &str) -> Option<ProjectName> {
+        match self.resolve_exact(query.trim()) {
+            Some(Ok(project)) => Some(project),
+            _ => None,
+        }
+    }
+
+    /// Find a project by ID, name, unique prefix or fuzzy match
+    #[allow(dead_code)] // Available for external use
+    pub fn resolve(&self, query: &str) -> Result<ProjectName, ProjectNameError> {
+        self.resolve_with(query, MatchMode::Fuzzy)
+    }
+
+    /// Find a project by ID, name or unique prefix, and in
+    /// [`MatchMode::Fuzzy`] also by fuzzy match
+    pub fn resolve_with(
+        &self,
+        query: &str,
+        mode: MatchMode,
+    ) -> Result<ProjectName, ProjectNameError> {
+        let query = query.trim();
+        if let Some(result) = self.resolve_exact(query) {
+            return result;
+        }
+
+        let query_lower = query.to_lowercase();
+        let names: Vec<(String, &ProjectName)> = self
+            .projects
+            .iter()
+            .map(|p| (p.name.to_lowercase(), p))
+            .collect();
+        let prefixed: Vec<&ProjectName> = names
+            .iter()
+            .filter(|(name, _)| name.starts_with(&query_lower))
+            .map(|(_, p)| *p)
+            .collect();
+        if !prefixed.is_empty() {
+            return single(query, prefixed);
+        }
+
+        let fuzzy = resolve_fuzzy(query, &query_lower, &names);
+        match (mode, fuzzy) {
+            (MatchMode::Fuzzy, result) => result,
+            (MatchMode::Strict, Ok(project)) => Err(ProjectNameError::Inexact {
+                name: query.to_string(),
+                candidates: vec![project],
+            }),
+            (MatchMode::Strict, Err(ProjectNameError::Ambiguous { name, candidates })) => {
+                Err(ProjectNameError::Inexact { name, candidates })
+            }
+            (MatchMode::Strict, Err(e)) => Err(e),
+        }
+    }
+
+    /// Like [`resolve()`](Self::resolve), recording the match for
+    /// [`inexact_matches()`] unless it was exact
+    pub fn resolve_recorded(
+        &self,
+        query: &str,
+        mode: MatchMode,
+    ) -> Result<ProjectName, ProjectNameError> {
+        let project = self.resolve_with(query, mode)?;
+        if self.find_exact(query).is_none() {
+            INEXACT_MATCHES
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(InexactMatch {
+                    query: query.trim().to_string(),
+                    id: project.id.clone(),
+                    name: project.name.clone(),
+                });
+        }
+        Ok(project)
+    }
+
+    fn resolve_exact(&self, query: &str) -> Option<Result<ProjectName, ProjectNameError>> {
+        if let Some(project) = self.projects.iter().find(|p| p.id == query) {
+            return Some(Ok(project.clone()));
+        }
+        if query.eq_ignore_ascii_case(INBOX_PROJECT_ID) {
+            let inbox = Project::inbox();
+            return Some(Ok(ProjectName {
+                id: inbox.id,
+                name: inbox.name,
+            }));
+        }
+
+        let found: Vec<&ProjectName> = self
+            .projects
+            .iter()
+            .filter(|p| p.name.eq_ignore_ascii_case(query))
+            .collect();
+        (!found.is_empty()).then(|| single(query, found))
+    }
+}
+
+/// Step 5: the only name containing the query, containing its letters in
+/// order, or closest to it within a small edit distance
+fn resolve_fuzzy(
+    query: &str,
+    query_lower: &str,
+    names: &[(String, &ProjectName)],
+) -> Result<ProjectName, ProjectNameError> {
+    let tiers: [&dyn Fn(&str) -> bool; 2] = [&|name| name.contains(query_lower), &|name| {
+        is_subsequence(query_lower, name)
+    }];
+    for matches in tiers {
+        let found: Vec<&ProjectName> = names
+            .iter()
+            .filter(|(name, _)| matches(name))
+            .map(|(_, p)| *p)
+            .collect();
+        if !found.is_empty() {
+            return single(query, found);
+        }
+    }
+
+    // Typos: the closest names within a small edit distance
+    let max_distance = if query.chars().count() <= 4 { 1 } else { 2 };
+    let distances: Vec<(usize, &ProjectName)> = names
+        .iter()
+        .map(|(name, p)| (edit_distance(query_lower, name), *p))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    match distances.iter().map(|(distance, _)| *distance).min() {
+        Some(min) => single(
+            query,
+            distances
+                .into_iter()
+                .filter(|(distance, _)| *distance == min)
+                .map(|(_, p)| p)
+                .collect(),
+        ),
+        None => Err(ProjectNameError::NotFound(query.to_string())),
+    }
+}
+
+/// The only project in `found`, or an ambiguity error
+fn single(query: &str, found: Vec<&ProjectName>) -> Result<ProjectName, ProjectNameError> {
+    match found.as_slice() {
+        [project] => Ok((*project).clone()),
+        _ => Err(ProjectNameError::Ambiguous {
+            name: query.to_string(),
+            candidates: found.into_iter().cloned().collect(),
+        }),
+    }
+}
+
+/// Replace a project alias (`[project_alias]`, keys ignore case) by its target
+pub fn expand_alias<'a>(name: &'a str, aliases: &'a BTreeMap<String, String>) -> &'a str {
+    let trimmed = name.trim();
+    aliases
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(trimmed))
+        .map_or(name, |(_, target)| target.as_str())
+}
+
+/// Whether the characters of `query` appear in `name` in order
+fn is_subsequence(query: &str, name: &str) -> bool {
+    let mut chars = name.chars();
+    query.chars().all(|c| chars.any(|n| n == c))
+}
+
+/// Edit distance counting insertions, deletions, substitutions and swaps of
+/// adjacent characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    rows[0] = (0..=b.len()).collect();
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(projects: &[(&str, &str)]) -> ProjectNames {
+        ProjectNames {
+            projects: projects
+                .iter()
+                .map(|(id, name)| ProjectName {
+                    id: id.to_string(),
+                    name: name.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    fn resolved(names: &ProjectNames, query: &str) -> String {
+        names.resolve(query).unwrap().id
+    }
+
+    fn candidates(names: &ProjectNames, query: &str) -> Vec<String> {
+        match names.resolve(query) {
+            Err(ProjectNameError::Ambiguous { candidates, .. }) => {
+                candidates.into_iter().map(|p| p.id).collect()
+            }
+            other => panic!("expected an ambiguous match, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_resolve_exact() {
+        let names = names(&[("p1", "Work"), ("p2", "Work Travel"), ("p3", "Home")]);
+        assert_eq!(resolved(&names, "work"), "p1");
+        assert_eq!(resolved(&names, " HOME "), "p3");
+        assert_eq!(resolved(&names, "p2"), "p2");
+        assert_eq!(names.find_exact("WORK").unwrap().id, "p1");
+        assert!(names.find_exact("Wo").is_none());
+    }
+
+    #[test]
+    fn test_resolve_inbox_without_projects() {
+        let names = ProjectNames::default();
+        assert_eq!(resolved(&names, "Inbox"), INBOX_PROJECT_ID);
+        assert_eq!(names.find_exact("inbox").unwrap().name, "Inbox");
+    }
+
+    #[test]
+    fn test_resolve_prefix() {
+        let names = names(&[("p1", "Work"), ("p2", "Weekend"), ("p3", "Home")]);
+        assert_eq!(resolved(&names, "wo"), "p1");
+        assert_eq!(resolved(&names, "h"), "p3");
+        assert_eq!(candidates(&names, "w"), vec!["p1", "p2"]);
+    }
+
+    #[test]
+    fn test_resolve_fuzzy() {
+        let names = names(&[
+            ("p1", "Work"),
+            ("p2", "Home Improvements"),
+            ("p3", "Reading List"),
+        ]);
+        // Substring
+        assert_eq!(resolved(&names, "improve"), "p2");
+        // Letters in order
+        assert_eq!(resolved(&names, "hmimp"), "p2");
+        assert_eq!(resolved(&names, "rdlst"), "p3");
+        // Typos
+        assert_eq!(resolved(&names, "wrok"), "p1");
+        assert_eq!(resolved(&names, "Readng Lst"), "p3");
+
+        assert!(matches!(
+            names.resolve("garden"),
+            Err(ProjectNameError::NotFound(name)) if name == "garden"
+        ));
+    }
+
+    #[test]
+    fn test_resolve_strict() {
+        let names = names(&[
+            ("p1", "Work"),
+            ("p2", "Weekend"),
+            ("p3", "Home Improvements"),
+        ]);
+        let strict = |query| names.resolve_with(query, MatchMode::Strict);
+        assert_eq!(strict("p3").unwrap().id, "p3");
+        assert_eq!(strict("inbox").unwrap().id, INBOX_PROJECT_ID);
+        assert_eq!(strict("WORK").unwrap().id, "p1");
+        assert_eq!(strict("wo").unwrap().id, "p1");
+        assert!(matches!(
+            strict("w"),
+            Err(ProjectNameError::Ambiguous { .. })
+        ));
+
+        let err = strict("wrok").unwrap_err();
+        assert_eq!(err.code(), ErrorCode::InvalidRequest);
+        assert_eq!(
+            err.to_string(),
+            "Project name 'wrok' only matches 'Work' (p1) approximately. \
+             Use the exact name, its start or --project-id"
+        );
+        assert!(matches!(
+            strict("e"),
+            Err(ProjectNameError::Inexact { candidates, .. }) if candidates.len() == 2
+        ));
+        assert!(matches!(
+            strict("garden"),
+            Err(ProjectNameError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_ambiguous_error_lists_candidates() {
+        let names = names(&[("p1", "Work"), ("p2", "work"), ("p3", "Home")]);
+        let err = names.resolve("Work").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Project name 'Work' is ambiguous, it matches 'Work' (p1), 'work' (p2). \
+             Use more of the name or --project-id"
+        );
+        assert_eq!(err.code(), ErrorCode::InvalidRequest);
+        assert_eq!(err.details().unwrap()["candidates"][1]["id"], "p2");
+        assert!(names.find_exact("work").is_none());
+    }
+
+    #[test]
+    fn test_expand_alias() {
+        let aliases = BTreeMap::from([("w".to_string(), "Work".to_string())]);
+        assert_eq!(expand_alias("W", &aliases), "Work");
+        assert_eq!(expand_alias("Home", &aliases), "Home");
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("work", "work"), 0);
+        assert_eq!(edit_distance("wrok", "work"), 1);
+        assert_eq!(edit_distance("wor", "work"), 1);
+        assert_eq!(edit_distance("home", "work"), 3);
+    }
+}
@@ -0,0 +1,96 @@
+//! Read-only mode
+//!
+//! With `--read-only` (or `TICKRS_READ_ONLY`), and for access tokens granted
+//! without the `tasks:write` scope, a client created with
+//! [`TickTickClient::new()`](crate::api::TickTickClient::new) refuses mutating
+//! requests before sending them. Other clients only do so when given a reason
+//! with [`with_read_only`](crate::api::TickTickClient::with_read_only). The CLI
+//! also rejects mutating commands up front, so they fail without any network
+//! call.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::constants::{ENV_READ_ONLY, SCOPE_TASKS_WRITE};
+
+/// Set by `--read-only` or `TICKRS_READ_ONLY`
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Why data can't be modified
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadOnlyReason {
+    /// Read-only mode was requested with `--read-only` or `TICKRS_READ_ONLY`
+    Requested,
+    /// The access token was granted without `tasks:write`
+    TokenScopes,
+}
+
+impl fmt::Display for ReadOnlyReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadOnlyReason::Requested => {
+                write!(f, "read-only mode is enabled (--read-only or {})", ENV_READ_ONLY)
+            }
+            ReadOnlyReason::TokenScopes => write!(
+                f,
+                "the access token was granted without {}. Run 'tickrs reset' and 'tickrs init' without --read-only to allow changes",
+                SCOPE_TASKS_WRITE
+            ),
+        }
+    }
+}
+
+/// Refuse mutating requests in all clients created afterwards with
+/// [`TickTickClient::new()`](crate::api::TickTickClient::new) (`--read-only`)
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Whether read-only mode was requested
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Why a token with these scopes can't modify data, if it can't.
+///
+/// Tokens with unknown scopes (e.g. from `TICKTICK_TOKEN`) are only limited
+/// by read-only mode.
+pub fn check(scopes: &[String]) -> Option<ReadOnlyReason> {
+    if is_enabled() {
+        Some(ReadOnlyReason::Requested)
+    } else if !scopes.is_empty() && !scopes.iter().any(|scope| scope == SCOPE_TASKS_WRITE) {
+        Some(ReadOnlyReason::TokenScopes)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scopes(scopes: &[&str]) -> Vec<String> {
+        scopes.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_check_token_scopes() {
+        // Read-only mode is process-wide and never enabled in unit tests
+        assert_eq!(check(&scopes(&["tasks:read", "tasks:write"])), None);
+        assert_eq!(check(&[]), None);
+        assert_eq!(
+            check(&scopes(&["tasks:read"])),
+            Some(ReadOnlyReason::TokenScopes)
+        );
+    }
+
+    #[test]
+    fn test_reason_display() {
+        assert!(ReadOnlyReason::Requested
+            .to_string()
+            .contains("TICKRS_READ_ONLY"));
+        assert!(ReadOnlyReason::TokenScopes
+            .to_string()
+            .contains("tasks:write"));
+    }
+}
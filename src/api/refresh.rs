@@ -0,0 +1,108 @@
+//! Automatic access token refresh
+//!
+//! A [`TokenRefresher`] owns the [`TokenSet`] of a
+//! [`TickTickClient`](crate::api::TickTickClient). It refreshes the access
+//! token shortly before it expires, or after the server rejected it, and can
+//! write the new tokens back to [`TokenStorage`].
+
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+use crate::api::auth::AuthHandler;
+use crate::api::client::ApiError;
+use crate::config::{TokenSet, TokenStorage};
+
+/// Refresh access tokens this many seconds before they expire
+pub const REFRESH_MARGIN_SECS: i64 = 60;
+
+/// Keeps the access token of a client valid
+pub struct TokenRefresher {
+    auth: AuthHandler,
+    tokens: Mutex<TokenSet>,
+    persist: bool,
+}
+
+impl TokenRefresher {
+    /// Create a refresher for `tokens`, using `auth` to talk to the token endpoint
+    pub fn new(auth: AuthHandler, tokens: TokenSet) -> Self {
+        Self {
+            auth,
+            tokens: Mutex::new(tokens),
+            persist: false,
+        }
+    }
+
+    /// Save refreshed tokens with [`TokenStorage::save_set`]
+    pub fn with_persistence(mut self, persist: bool) -> Self {
+        self.persist = persist;
+        self
+    }
+
+    /// The current token set
+    #[allow(dead_code)] // Available for external use
+    pub async fn tokens(&self) -> TokenSet {
+        self.tokens.lock().await.clone()
+    }
+
+    /// Access token for the next request, refreshed first if it is about to expire.
+    ///
+    /// If refreshing fails while the current token is still valid, the
+    /// current token is used.
+    pub async fn access_token(&self) -> Result<String, ApiError> {
+        let mut tokens = self.tokens.lock().await;
+        let margin = chrono::Duration::seconds(REFRESH_MARGIN_SECS);
+        if tokens.refresh_token.is_some() && tokens.expires_within(margin) {
+            debug!("Access token expires soon, refreshing");
+            if let Err(e) = self.refresh_locked(&mut tokens).await {
+                if tokens.expires_within(chrono::Duration::zero()) {
+                    return Err(e);
+                }
+                warn!("{}; using the current access token", e);
+            }
+        }
+        Ok(tokens.access_token.clone())
+    }
+
+    /// Refresh after the server rejected `rejected`, returning the new access token.
+    ///
+    /// When several requests are rejected at once, only the first one
+    /// refreshes; the others get the token it obtained.
+    pub async fn refresh(&self, rejected: &str) -> Result<String, ApiError> {
+        let mut tokens = self.tokens.lock().await;
+        if tokens.access_token == rejected {
+            self.refresh_locked(&mut tokens).await?;
+        }
+        Ok(tokens.access_token.clone())
+    }
+
+    async fn refresh_locked(&self, tokens: &mut TokenSet) -> Result<(), ApiError> {
+        let refresh_token = tokens.refresh_token.clone().ok_or_else(|| {
+            ApiError::TokenRefreshFailed("no refresh token available".to_string())
+        })?;
+
+        let mut refreshed = self
+            .auth
+            .refresh(&refresh_token)
+            .await
+            .map_err(|e| ApiError::TokenRefreshFailed(format!("{:#}", e)))?;
+
+        // Carry over what the token endpoint doesn't return again
+        if refreshed.refresh_token.is_none() {
+            refreshed.refresh_token = Some(refresh_token);
+        }
+        if refreshed.scopes.is_empty() {
+            refreshed.scopes = std::mem::take(&mut tokens.scopes);
+        }
+        if refreshed.client_secret.is_none() {
+            refreshed.client_secret = tokens.client_secret.take();
+        }
+        *tokens = refreshed;
+
+        if self.persist {
+            if let Err(e) = TokenStorage::save_set(tokens) {
+                warn!("Failed to save refreshed access token: {:#}", e);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,277 @@
+//! Retry policy for transient API failures
+//!
+//! Implements exponential backoff with jitter for rate-limited (429),
+//! server error (5xx) and transient network failures. A `Retry-After`
+//! header sent by the server takes precedence over the computed backoff.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use reqwest::Method;
+
+use crate::config::RetryConfig;
+
+/// Default number of attempts, including the initial request
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Default delay before the first retry
+pub const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Default upper bound for a single delay
+pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Retry behaviour for [`TickTickClient`](crate::api::TickTickClient) requests.
+///
+/// Delays grow exponentially from `base_delay` (doubling per attempt) and are
+/// randomized between half and the full computed value to avoid many clients
+/// retrying in lockstep. If the server sends a `Retry-After` header that
+/// exceeds `max_delay`, the request is not retried at all.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use ticktickrs::api::RetryPolicy;
+///
+/// let policy = RetryPolicy::default()
+///     .with_max_attempts(5)
+///     .with_base_delay(Duration::from_millis(200))
+///     .with_retry_non_idempotent(true);
+/// assert_eq!(policy.max_attempts, 5);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the initial request
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for every further attempt
+    pub base_delay: Duration,
+    /// Upper bound for a single delay (computed or `Retry-After`)
+    pub max_delay: Duration,
+    /// Whether non-idempotent requests (POST) may be retried
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_delay: DEFAULT_BASE_DELAY,
+            max_delay: DEFAULT_MAX_DELAY,
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+#[allow(dead_code)] // Builder methods available for external use; tested
+impl RetryPolicy {
+    /// A policy that never retries
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Set the maximum number of attempts (at least 1)
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set the delay before the first retry
+    pub fn with_base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    /// Set the upper bound for a single delay
+    pub fn with_max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Allow retrying non-idempotent requests such as POST
+    pub fn with_retry_non_idempotent(mut self, enabled: bool) -> Self {
+        self.retry_non_idempotent = enabled;
+        self
+    }
+
+    /// Check whether a request may be retried after `attempt` attempts
+    pub fn allows_retry(&self, method: &Method, attempt: u32) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+        is_idempotent(method) || self.retry_non_idempotent
+    }
+
+    /// Compute the delay before the next attempt.
+    ///
+    /// `attempt` is the number of attempts made so far (starting at 1).
+    /// Returns `None` if the server asked us to wait longer than `max_delay`.
+    pub fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Option<Duration> {
+        if let Some(retry_after) = retry_after {
+            return (retry_after <= self.max_delay).then_some(retry_after);
+        }
+
+        let exponent = attempt.saturating_sub(1).min(16);
+        let backoff = self
+            .base_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay);
+
+        // "Equal jitter": wait between half and the full backoff
+        let half = backoff / 2;
+        Some(half + jitter(backoff - half))
+    }
+}
+
+impl From<&RetryConfig> for RetryPolicy {
+    fn from(config: &RetryConfig) -> Self {
+        Self::default()
+            .with_max_attempts(config.max_attempts)
+            .with_retry_non_idempotent(config.retry_non_idempotent)
+    }
+}
+
+/// Whether a request with this method can safely be sent more than once
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS
+    )
+}
+
+/// Parse a `Retry-After` header value (delta-seconds or HTTP-date)
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = date.with_timezone(&Utc) - Utc::now();
+    Some(wait.to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Random duration in `[0, max]`
+fn jitter(max: Duration) -> Duration {
+    let max_nanos = max.as_nanos() as u64;
+    if max_nanos == 0 {
+        return Duration::ZERO;
+    }
+
+    // RandomState is seeded randomly per instance, which is plenty for jitter
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(max_nanos);
+    Duration::from_nanos(hasher.finish() % (max_nanos + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.max_attempts, 3);
+        assert!(!policy.retry_non_idempotent);
+    }
+
+    #[test]
+    fn test_none_policy_never_retries() {
+        let policy = RetryPolicy::none();
+        assert!(!policy.allows_retry(&Method::GET, 1));
+    }
+
+    #[test]
+    fn test_max_attempts_is_at_least_one() {
+        let policy = RetryPolicy::default().with_max_attempts(0);
+        assert_eq!(policy.max_attempts, 1);
+    }
+
+    #[test]
+    fn test_allows_retry_respects_max_attempts() {
+        let policy = RetryPolicy::default().with_max_attempts(3);
+        assert!(policy.allows_retry(&Method::GET, 1));
+        assert!(policy.allows_retry(&Method::GET, 2));
+        assert!(!policy.allows_retry(&Method::GET, 3));
+    }
+
+    #[test]
+    fn test_post_not_retried_unless_opted_in() {
+        let policy = RetryPolicy::default();
+        assert!(policy.allows_retry(&Method::DELETE, 1));
+        assert!(!policy.allows_retry(&Method::POST, 1));
+
+        let policy = policy.with_retry_non_idempotent(true);
+        assert!(policy.allows_retry(&Method::POST, 1));
+    }
+
+    #[test]
+    fn test_delay_grows_exponentially_within_bounds() {
+        let policy = RetryPolicy::default().with_base_delay(Duration::from_millis(100));
+
+        for attempt in 1..=4 {
+            let backoff = Duration::from_millis(100 * (1 << (attempt - 1)));
+            let delay = policy.delay(attempt, None).unwrap();
+            assert!(delay >= backoff / 2, "attempt {}: {:?}", attempt, delay);
+            assert!(delay <= backoff, "attempt {}: {:?}", attempt, delay);
+        }
+    }
+
+    #[test]
+    fn test_delay_capped_at_max_delay() {
+        let policy = RetryPolicy::default()
+            .with_base_delay(Duration::from_secs(10))
+            .with_max_delay(Duration::from_secs(15));
+
+        let delay = policy.delay(10, None).unwrap();
+        assert!(delay <= Duration::from_secs(15));
+    }
+
+    #[test]
+    fn test_delay_honors_retry_after() {
+        let policy = RetryPolicy::default();
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_secs(2))),
+            Some(Duration::from_secs(2))
+        );
+    }
+
+    #[test]
+    fn test_delay_gives_up_when_retry_after_too_long() {
+        let policy = RetryPolicy::default().with_max_delay(Duration::from_secs(5));
+        assert_eq!(policy.delay(1, Some(Duration::from_secs(60))), None);
+    }
+
+    #[test]
+    fn test_parse_retry_after_seconds() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after(" 0 "), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_parse_retry_after_http_date_in_past() {
+        let delay = parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT");
+        assert_eq!(delay, Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_parse_retry_after_invalid() {
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[test]
+    fn test_policy_from_config() {
+        let config = RetryConfig {
+            max_attempts: 5,
+            retry_non_idempotent: true,
+        };
+        let policy = RetryPolicy::from(&config);
+        assert_eq!(policy.max_attempts, 5);
+        assert!(policy.retry_non_idempotent);
+    }
+}
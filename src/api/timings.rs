@@ -0,0 +1,285 @@
+//! Request timing report (`--timings`)
+//!
+//! When enabled, every request made through a
+//! [`TickTickClient`](crate::api::TickTickClient) is recorded with its method,
+//! endpoint, final status, latency and number of retries. The CLI prints the
+//! report on stderr, or includes it as `meta.timings` in JSON output.
+
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// Process-wide recorder, `None` unless timings are enabled
+static RECORDER: Mutex<Option<Recorder>> = Mutex::new(None);
+
+/// One request as seen by the caller, including all retries
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RequestTiming {
+    /// HTTP method
+    pub method: String,
+    /// API endpoint (e.g. "/project/abc/data")
+    pub endpoint: String,
+    /// Final HTTP status, absent if no response was received
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    /// Time from the first attempt until the final response, including
+    /// rate limit waits and retry delays
+    pub latency_ms: u64,
+    /// Number of retries after the first attempt
+    pub retries: u32,
+}
+
+/// Summary over all recorded requests
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TimingTotals {
+    /// Number of requests (not counting retries)
+    pub requests: usize,
+    /// Number of retried attempts
+    pub retries: u32,
+    /// Number of `429 Too Many Requests` responses, including retried ones
+    pub rate_limited: u32,
+    /// Number of requests that failed or returned an error status
+    pub errors: usize,
+    /// Number of responses served from the on-disk cache
+    pub cache_hits: u32,
+    /// Sum of all request latencies
+    pub latency_ms: u64,
+    /// Wall-clock time since recording started
+    pub elapsed_ms: u64,
+}
+
+/// All recorded requests and their totals
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TimingReport {
+    /// Requests in the order they completed
+    pub requests: Vec<RequestTiming>,
+    /// Summary over all requests
+    pub totals: TimingTotals,
+}
+
+/// Collects request timings
+#[derive(Debug)]
+struct Recorder {
+    started: Instant,
+    requests: Vec<RequestTiming>,
+    rate_limited: u32,
+    cache_hits: u32,
+}
+
+impl Recorder {
+    fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            requests: Vec::new(),
+            rate_limited: 0,
+            cache_hits: 0,
+        }
+    }
+
+    fn report(&self) -> TimingReport {
+        let totals = TimingTotals {
+            requests: self.requests.len(),
+            retries: self.requests.iter().map(|r| r.retries).sum(),
+            rate_limited: self.rate_limited,
+            errors: self
+                .requests
+                .iter()
+                .filter(|r| r.status.is_none_or(|status| status >= 400))
+                .count(),
+            cache_hits: self.cache_hits,
+            latency_ms: self.requests.iter().map(|r| r.latency_ms).sum(),
+            elapsed_ms: millis(self.started.elapsed()),
+        };
+        TimingReport {
+            requests: self.requests.clone(),
+            totals,
+        }
+    }
+}
+
+fn with_recorder(f: impl FnOnce(&mut Recorder)) {
+    let mut recorder = RECORDER.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(recorder) = recorder.as_mut() {
+        f(recorder);
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_millis().try_into().unwrap_or(u64::MAX)
+}
+
+/// Start recording request timings for this process
+pub fn enable() {
+    let mut recorder = RECORDER.lock().unwrap_or_else(|e| e.into_inner());
+    if recorder.is_none() {
+        *recorder = Some(Recorder::new());
+    }
+}
+
+/// Whether request timings are being recorded
+#[allow(dead_code)] // Available for external use
+pub fn is_enabled() -> bool {
+    RECORDER.lock().unwrap_or_else(|e| e.into_inner()).is_some()
+}
+
+/// Record a completed request (no-op unless enabled)
+pub(crate) fn record_request(
+    method: &str,
+    endpoint: &str,
+    status: Option<u16>,
+    latency: Duration,
+    attempts: u32,
+) {
+    with_recorder(|recorder| {
+        recorder.requests.push(RequestTiming {
+            method: method.to_string(),
+            endpoint: endpoint.to_string(),
+            status,
+            latency_ms: millis(latency),
+            retries: attempts.saturating_sub(1),
+        })
+    });
+}
+
+/// Record a `429 Too Many Requests` response (no-op unless enabled)
+pub(crate) fn record_rate_limited() {
+    with_recorder(|recorder| recorder.rate_limited += 1);
+}
+
+/// Record a response served from the cache (no-op unless enabled)
+pub(crate) fn record_cache_hit() {
+    with_recorder(|recorder| recorder.cache_hits += 1);
+}
+
+/// The report so far, or `None` if timings are not enabled
+pub fn report() -> Option<TimingReport> {
+    RECORDER
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map(Recorder::report)
+}
+
+impl fmt::Display for TimingReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Timings:")?;
+        for request in &self.requests {
+            let status = request
+                .status
+                .map_or_else(|| "ERR".to_string(), |status| status.to_string());
+            write!(
+                f,
+                "  {:<6} {:<40} {:>3} {:>6}ms",
+                request.method, request.endpoint, status, request.latency_ms
+            )?;
+            if request.retries > 0 {
+                write!(
+                    f,
+                    "  ({} {})",
+                    request.retries,
+                    plural(request.retries, "retry", "retries")
+                )?;
+            }
+            writeln!(f)?;
+        }
+
+        let totals = &self.totals;
+        write!(
+            f,
+            "  {} {}, {} {}, {} rate limited, {} {}, {} cache {}, {}ms in requests, {}ms total",
+            totals.requests,
+            plural(totals.requests as u32, "request", "requests"),
+            totals.retries,
+            plural(totals.retries, "retry", "retries"),
+            totals.rate_limited,
+            totals.errors,
+            plural(totals.errors as u32, "error", "errors"),
+            totals.cache_hits,
+            plural(totals.cache_hits, "hit", "hits"),
+            totals.latency_ms,
+            totals.elapsed_ms
+        )
+    }
+}
+
+fn plural(count: u32, one: &'static str, many: &'static str) -> &'static str {
+    if count == 1 {
+        one
+    } else {
+        many
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recorder_with_requests() -> Recorder {
+        let mut recorder = Recorder::new();
+        recorder.requests = vec![
+            RequestTiming {
+                method: "GET".to_string(),
+                endpoint: "/project".to_string(),
+                status: Some(200),
+                latency_ms: 120,
+                retries: 0,
+            },
+            RequestTiming {
+                method: "GET".to_string(),
+                endpoint: "/project/abc/data".to_string(),
+                status: Some(200),
+                latency_ms: 1500,
+                retries: 2,
+            },
+            RequestTiming {
+                method: "POST".to_string(),
+                endpoint: "/task".to_string(),
+                status: Some(400),
+                latency_ms: 80,
+                retries: 0,
+            },
+            RequestTiming {
+                method: "DELETE".to_string(),
+                endpoint: "/project/abc".to_string(),
+                status: None,
+                latency_ms: 30000,
+                retries: 0,
+            },
+        ];
+        recorder.rate_limited = 2;
+        recorder.cache_hits = 1;
+        recorder
+    }
+
+    #[test]
+    fn test_report_totals() {
+        let totals = recorder_with_requests().report().totals;
+        assert_eq!(totals.requests, 4);
+        assert_eq!(totals.retries, 2);
+        assert_eq!(totals.rate_limited, 2);
+        assert_eq!(totals.errors, 2);
+        assert_eq!(totals.cache_hits, 1);
+        assert_eq!(totals.latency_ms, 31700);
+    }
+
+    #[test]
+    fn test_report_display() {
+        let text = recorder_with_requests().report().to_string();
+        assert!(text.starts_with("Timings:\n"));
+        assert!(text.contains("/project/abc/data"));
+        assert!(text.contains("(2 retries)"));
+        assert!(text.contains("ERR"));
+        assert!(text.contains("4 requests, 2 retries, 2 rate limited, 2 errors, 1 cache hit"));
+    }
+
+    #[test]
+    fn test_report_serialization() {
+        let json = serde_json::to_value(recorder_with_requests().report()).unwrap();
+        assert_eq!(json["requests"][0]["endpoint"], "/project");
+        assert_eq!(json["requests"][1]["retries"], 2);
+        assert!(json["requests"][3].get("status").is_none());
+        assert_eq!(json["totals"]["requests"], 4);
+    }
+}
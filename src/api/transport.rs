@@ -0,0 +1,222 @@
+//! HTTP transport abstraction for [`TickTickClient`](crate::api::TickTickClient)
+//!
+//! The client builds an [`HttpRequest`], hands it to a [`Transport`] and maps
+//! the returned [`HttpResponse`] to API results and errors. The default
+//! transport is [`ReqwestTransport`]; tests and other crates can plug in an
+//! in-memory implementation to run the client without a network.
+//!
+//! # Example
+//!
+//! ```
+//! use ticktickrs::api::transport::{HttpRequest, HttpResponse, Transport, TransportFuture};
+//! use ticktickrs::api::TickTickClient;
+//!
+//! /// Answers every request with an empty project list
+//! struct EmptyProjects;
+//!
+//! impl Transport for EmptyProjects {
+//!     fn send(&self, _request: HttpRequest) -> TransportFuture<'_> {
+//!         Box::pin(async { Ok(HttpResponse::new(200, "[]")) })
+//!     }
+//! }
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let client = TickTickClient::with_token("token".to_string())?.with_transport(EmptyProjects);
+//! let projects = client.list_projects().await?;
+//! assert_eq!(projects.len(), 1); // Only the built-in Inbox
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+
+use reqwest::header::HeaderMap;
+use reqwest::{Client, Method, StatusCode};
+
+/// A request to be sent by a [`Transport`]
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: Method,
+    /// Absolute URL including the API base URL
+    pub url: String,
+    /// Request headers, including `Authorization`
+    pub headers: HeaderMap,
+    /// JSON request body, if any
+    pub body: Option<Vec<u8>>,
+}
+
+/// A response returned by a [`Transport`]
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Create a response with the given status code and body and no headers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `status` is not a valid HTTP status code.
+    #[allow(dead_code)] // Available for external use
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status: StatusCode::from_u16(status).expect("invalid HTTP status code"),
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+
+    /// The response body as text (invalid UTF-8 is replaced)
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+/// Kind of failure reported by a [`Transport`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportErrorKind {
+    /// Could not establish a connection
+    Connect,
+    /// The connection or response timed out
+    Timeout,
+    /// Any other failure
+    Other,
+}
+
+/// Error returned when a request could not be completed
+#[derive(Debug)]
+pub struct TransportError {
+    kind: TransportErrorKind,
+    source: Box<dyn std::error::Error + Send + Sync>,
+}
+
+impl TransportError {
+    /// Create a transport error from any error type
+    pub fn new(
+        kind: TransportErrorKind,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        Self {
+            kind,
+            source: source.into(),
+        }
+    }
+
+    /// The kind of failure
+    #[allow(dead_code)] // Available for external use
+    pub fn kind(&self) -> TransportErrorKind {
+        self.kind
+    }
+
+    /// Whether the request timed out
+    pub fn is_timeout(&self) -> bool {
+        self.kind == TransportErrorKind::Timeout
+    }
+
+    /// Whether a connection could not be established
+    pub fn is_connect(&self) -> bool {
+        self.kind == TransportErrorKind::Connect
+    }
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.source.fmt(f)
+    }
+}
+
+impl std::error::Error for TransportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+impl From<reqwest::Error> for TransportError {
+    fn from(err: reqwest::Error) -> Self {
+        let kind = if err.is_timeout() {
+            TransportErrorKind::Timeout
+        } else if err.is_connect() {
+            TransportErrorKind::Connect
+        } else {
+            TransportErrorKind::Other
+        };
+        Self::new(kind, err)
+    }
+}
+
+/// Future returned by [`Transport::send`]
+pub type TransportFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<HttpResponse, TransportError>> + Send + 'a>>;
+
+/// Sends HTTP requests on behalf of the API client
+pub trait Transport: Send + Sync {
+    /// Send a request and return the response status, headers and body
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_>;
+}
+
+/// Default transport backed by `reqwest`
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl ReqwestTransport {
+    /// Create a transport using the given reqwest client
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let mut builder = self
+                .client
+                .request(request.method, request.url)
+                .headers(request.headers);
+            if let Some(body) = request.body {
+                builder = builder.body(body);
+            }
+
+            let response = builder.send().await?;
+            let status = response.status();
+            let headers = response.headers().clone();
+            let body = response.bytes().await?.to_vec();
+
+            Ok(HttpResponse {
+                status,
+                headers,
+                body,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_http_response_new() {
+        let response = HttpResponse::new(201, r#"{"id":"1"}"#);
+        assert_eq!(response.status, StatusCode::CREATED);
+        assert!(response.headers.is_empty());
+        assert_eq!(response.text(), r#"{"id":"1"}"#);
+    }
+
+    #[test]
+    fn test_transport_error_kind() {
+        let err = TransportError::new(TransportErrorKind::Timeout, "timed out");
+        assert!(err.is_timeout());
+        assert!(!err.is_connect());
+        assert_eq!(err.to_string(), "timed out");
+
+        let err = TransportError::new(TransportErrorKind::Connect, "connection refused");
+        assert!(err.is_connect());
+        assert_eq!(err.kind(), TransportErrorKind::Connect);
+    }
+}
@@ -0,0 +1,216 @@
+//! User-defined command aliases (`[alias]` in `config.toml`)
+//!
+//! An alias replaces the command name with its expansion, split like a shell
+//! command line, before the arguments reach clap:
+//!
+//! ```toml
+//! [alias]
+//! inbox-high = "task list -n Inbox --priority high --status incomplete"
+//! ```
+//!
+//! `tickrs inbox-high --json` then runs
+//! `tickrs task list -n Inbox --priority high --status incomplete --json`.
+//! Aliases may expand to other aliases, but not to themselves. Built-in
+//! commands can't be redefined.
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+use clap::Command;
+
+/// Global options taking a separate value, e.g. `--profile work`
+const OPTIONS_WITH_VALUE: &[&str] = &["--profile", "--log-file"];
+
+/// Index of the command name in `args` (the program name is `args[0]`)
+fn command_position(args: &[String]) -> Option<usize> {
+    let mut i = 1;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        if arg == "--" {
+            return None;
+        }
+        if !arg.starts_with('-') {
+            return Some(i);
+        }
+        if OPTIONS_WITH_VALUE.contains(&arg) {
+            i += 1;
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Value of `--profile` in `args`, which selects the aliases to use
+pub fn profile_arg(args: &[String]) -> Option<&str> {
+    let mut args = args.iter().skip(1).take_while(|arg| *arg != "--");
+    while let Some(arg) = args.next() {
+        if arg == "--profile" {
+            return args.next().map(String::as_str);
+        }
+        if let Some(value) = arg.strip_prefix("--profile=") {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Whether `name` is a built-in command (or one of its aliases)
+fn is_builtin(cli: &Command, name: &str) -> bool {
+    name == "help"
+        || cli
+            .get_subcommands()
+            .any(|cmd| cmd.get_name() == name || cmd.get_all_aliases().any(|alias| alias == name))
+}
+
+/// Name of the user alias invoked by `args`, if any
+pub fn invoked<'a>(cli: &Command, args: &'a [String]) -> Option<&'a str> {
+    command_position(args)
+        .map(|i| args[i].as_str())
+        .filter(|name| !is_builtin(cli, name))
+}
+
+/// Replace user aliases in `args` with their expansions
+pub fn expand(
+    cli: &Command,
+    mut args: Vec<String>,
+    aliases: &BTreeMap<String, String>,
+) -> Result<Vec<String>> {
+    let mut chain: Vec<String> = Vec::new();
+
+    while let Some(i) = command_position(&args) {
+        let name = args[i].clone();
+        if is_builtin(cli, &name) {
+            break;
+        }
+        let Some(expansion) = aliases.get(&name) else {
+            break;
+        };
+
+        if chain.contains(&name) {
+            chain.push(name);
+            bail!("Alias loop detected: {}", chain.join(" -> "));
+        }
+        let Some(words) = shlex::split(expansion) else {
+            bail!(
+                "Invalid alias '{}': unbalanced quotes in '{}'",
+                name,
+                expansion
+            );
+        };
+        if words.is_empty() {
+            bail!("Invalid alias '{}': the expansion is empty", name);
+        }
+
+        args.splice(i..=i, words);
+        chain.push(name);
+    }
+
+    Ok(args)
+}
+
+/// List of aliases shown at the end of `--help`
+pub fn help(aliases: &BTreeMap<String, String>) -> String {
+    let width = aliases.keys().map(|name| name.len()).max().unwrap_or(0);
+    let mut output = String::from("Aliases:\n");
+    for (name, expansion) in aliases {
+        output.push_str(&format!(
+            "  {:width$}  {}\n",
+            name,
+            expansion,
+            width = width
+        ));
+    }
+    output.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    fn args(line: &str) -> Vec<String> {
+        shlex::split(line).unwrap()
+    }
+
+    fn aliases(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(name, expansion)| (name.to_string(), expansion.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_expand_alias() {
+        let cli = crate::cli::Cli::command();
+        let aliases = aliases(&[
+            ("inbox-high", "task list -n Inbox --priority high"),
+            ("note", "task create --content 'see \"docs\"' --title"),
+        ]);
+
+        let expanded = expand(&cli, args("tickrs -q inbox-high --json"), &aliases).unwrap();
+        assert_eq!(
+            expanded,
+            args("tickrs -q task list -n Inbox --priority high --json")
+        );
+
+        let expanded = expand(&cli, args("tickrs note 'Buy milk'"), &aliases).unwrap();
+        assert_eq!(expanded[4], "see \"docs\"");
+        assert_eq!(expanded[6], "Buy milk");
+
+        // Values of global options are not commands
+        let expanded = expand(&cli, args("tickrs --profile note inbox-high"), &aliases).unwrap();
+        assert_eq!(expanded[3], "task");
+    }
+
+    #[test]
+    fn test_builtin_commands_are_not_expanded() {
+        let cli = crate::cli::Cli::command();
+        let aliases = aliases(&[("task", "project list"), ("ls", "project list")]);
+
+        let original = args("tickrs task list");
+        assert_eq!(expand(&cli, original.clone(), &aliases).unwrap(), original);
+        assert!(invoked(&cli, &original).is_none());
+        assert_eq!(invoked(&cli, &args("tickrs -v inbox")), Some("inbox"));
+        assert!(invoked(&cli, &args("tickrs --json")).is_none());
+    }
+
+    #[test]
+    fn test_alias_chains_and_loops() {
+        let cli = crate::cli::Cli::command();
+        let aliases = aliases(&[
+            ("a", "b --json"),
+            ("b", "task list"),
+            ("x", "y"),
+            ("y", "x"),
+            ("broken", "task list -n 'Inbox"),
+        ]);
+
+        let expanded = expand(&cli, args("tickrs a"), &aliases).unwrap();
+        assert_eq!(expanded, args("tickrs task list --json"));
+
+        let err = expand(&cli, args("tickrs x"), &aliases).unwrap_err();
+        assert_eq!(err.to_string(), "Alias loop detected: x -> y -> x");
+
+        let err = expand(&cli, args("tickrs broken"), &aliases).unwrap_err();
+        assert!(err.to_string().contains("unbalanced quotes"));
+    }
+
+    #[test]
+    fn test_profile_arg() {
+        assert_eq!(profile_arg(&args("tickrs --profile work x")), Some("work"));
+        assert_eq!(profile_arg(&args("tickrs x --profile=home")), Some("home"));
+        assert_eq!(profile_arg(&args("tickrs x -- --profile work")), None);
+    }
+
+    #[test]
+    fn test_help() {
+        let help = help(&aliases(&[
+            ("ih", "task list"),
+            ("today", "task list --all"),
+        ]));
+        assert_eq!(
+            help,
+            "Aliases:\n  ih     task list\n  today  task list --all"
+        );
+    }
+}
@@ -0,0 +1,25 @@
+use clap::Subcommand;
+
+#[derive(Subcommand, Debug)]
+pub enum AuthCommands {
+    /// Show where the token comes from and check that it works
+    Status,
+
+    /// Store tokens without a browser, e.g. to provision CI or containers
+    Login {
+        /// Read an access token (optionally followed by a refresh token on
+        /// the next line) or a JSON bundle from 'auth export' from stdin
+        #[arg(long, required = true)]
+        token_stdin: bool,
+    },
+
+    /// Print the stored tokens as JSON, for 'auth login --token-stdin'.
+    /// The output is a credential; treat it like a password
+    Export {
+        /// Include the OAuth client secret (stored with the tokens or from
+        /// TICKTICK_CLIENT_SECRET), so the target can refresh the tokens
+        /// without it in its environment
+        #[arg(long)]
+        include_client_secret: bool,
+    },
+}
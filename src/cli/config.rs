@@ -0,0 +1,35 @@
+use clap::Subcommand;
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommands {
+    /// Show the value of a setting, e.g. 'retry.max_attempts'
+    Get {
+        /// Setting name
+        key: String,
+    },
+
+    /// Change a setting in the config file
+    Set {
+        /// Setting name
+        key: String,
+
+        /// New value (comma-separated for lists)
+        value: String,
+    },
+
+    /// Remove a setting from the config file, restoring its default
+    Unset {
+        /// Setting name
+        key: String,
+    },
+
+    /// List all settings with their values
+    #[command(alias = "ls")]
+    List,
+
+    /// Print the path of the config file
+    Path,
+
+    /// Open the config file in $VISUAL or $EDITOR
+    Edit,
+}
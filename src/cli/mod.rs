@@ -1,3 +1,7 @@
+pub mod alias;
+pub mod auth;
+pub mod config;
+pub mod profile;
 pub mod project;
 pub mod root;
 pub mod subtask;
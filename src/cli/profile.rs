@@ -0,0 +1,30 @@
+use clap::Subcommand;
+
+#[derive(Subcommand, Debug)]
+pub enum ProfileCommands {
+    /// List all profiles
+    #[command(alias = "ls")]
+    List,
+
+    /// Create a new profile
+    Add {
+        /// Profile name (letters, digits, '-' and '_')
+        name: String,
+    },
+
+    /// Delete a profile with its configuration and credentials
+    Remove {
+        /// Profile name
+        name: String,
+
+        /// Skip confirmation prompt
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Set the profile used when --profile and TICKRS_PROFILE are not given
+    Use {
+        /// Profile name
+        name: String,
+    },
+}
@@ -65,3 +65,16 @@ pub enum ProjectCommands {
         force: bool,
     },
 }
+
+impl ProjectCommands {
+    /// Whether the command changes projects in TickTick ('use' only changes
+    /// the local default project)
+    pub fn is_mutating(&self) -> bool {
+        matches!(
+            self,
+            ProjectCommands::Create { .. }
+                | ProjectCommands::Update { .. }
+                | ProjectCommands::Delete { .. }
+        )
+    }
+}
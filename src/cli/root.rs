@@ -1,5 +1,11 @@
-use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
+use clap::builder::BoolishValueParser;
+use clap::{ArgAction, Parser, Subcommand};
+
+use super::auth::AuthCommands;
+use super::config::ConfigCommands;
+use super::profile::ProfileCommands;
 use super::project::ProjectCommands;
 use super::subtask::SubtaskCommands;
 use super::task::TaskCommands;
@@ -17,9 +23,34 @@ pub struct Cli {
     #[arg(long, short = 'q', global = true)]
     pub quiet: bool,
 
-    /// Enable verbose output
-    #[arg(long, short = 'v', global = true)]
-    pub verbose: bool,
+    /// Log diagnostics to stderr (-v for info, -vv for request details)
+    #[arg(long, short = 'v', global = true, action = ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Append JSON-formatted logs to a file
+    #[arg(long, global = true, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
+    /// Ignore cached project data and fetch fresh responses
+    #[arg(long, global = true)]
+    pub no_cache: bool,
+
+    /// Report every API request with status, latency and retries
+    #[arg(long, global = true)]
+    pub timings: bool,
+
+    /// Account profile to use (overrides TICKRS_PROFILE and 'profile use')
+    #[arg(long, global = true, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Refuse commands that modify data; with init, request read-only access
+    #[arg(
+        long,
+        global = true,
+        env = crate::constants::ENV_READ_ONLY,
+        value_parser = BoolishValueParser::new()
+    )]
+    pub read_only: bool,
 
     #[command(subcommand)]
     pub command: Commands,
@@ -28,7 +59,20 @@ pub struct Cli {
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Initialize OAuth authentication with TickTick
-    Init,
+    Init {
+        /// Don't open a browser or wait for the redirect; paste the
+        /// redirect URL or authorization code instead (e.g. over SSH)
+        #[arg(long)]
+        no_browser: bool,
+
+        /// Host of the OAuth redirect URI [default: localhost]
+        #[arg(long, value_name = "HOST")]
+        callback_host: Option<String>,
+
+        /// Port of the OAuth redirect URI [default: 8080]
+        #[arg(long, value_name = "PORT")]
+        callback_port: Option<u16>,
+    },
 
     /// Reset configuration and clear stored token
     Reset {
@@ -40,6 +84,18 @@ pub enum Commands {
     /// Display version information
     Version,
 
+    /// Authentication commands
+    #[command(subcommand)]
+    Auth(AuthCommands),
+
+    /// View and change settings of the active profile
+    #[command(subcommand)]
+    Config(ConfigCommands),
+
+    /// Account profile management commands
+    #[command(subcommand)]
+    Profile(ProfileCommands),
+
     /// Project management commands
     #[command(subcommand)]
     Project(ProjectCommands),
@@ -52,3 +108,20 @@ pub enum Commands {
     #[command(subcommand)]
     Subtask(SubtaskCommands),
 }
+
+impl Commands {
+    /// Whether the command creates, changes or deletes data in TickTick
+    pub fn is_mutating(&self) -> bool {
+        match self {
+            Commands::Project(cmd) => cmd.is_mutating(),
+            Commands::Task(cmd) => cmd.is_mutating(),
+            Commands::Init { .. }
+            | Commands::Reset { .. }
+            | Commands::Version
+            | Commands::Auth(_)
+            | Commands::Config(_)
+            | Commands::Profile(_)
+            | Commands::Subtask(_) => false,
+        }
+    }
+}
@@ -15,6 +15,10 @@ pub enum TaskCommands {
         #[arg(long, short = 'n')]
         project_name: Option<String>,
 
+        /// List tasks across all projects, including Inbox
+        #[arg(long, short, conflicts_with_all = ["project_id", "project_name"])]
+        all: bool,
+
         /// Filter by priority
         #[arg(long)]
         priority: Option<Priority>,
@@ -81,9 +85,9 @@ pub enum TaskCommands {
         #[arg(long)]
         due: Option<String>,
 
-        /// Mark as all-day task
-        #[arg(long)]
-        all_day: bool,
+        /// Mark as all-day task (`--all-day=false` overrides task_defaults.all_day)
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+        all_day: Option<bool>,
 
         /// Timezone
         #[arg(long)]
@@ -166,10 +170,11 @@ pub enum TaskCommands {
         force: bool,
     },
 
-    /// Mark a task as complete
+    /// Mark one or more tasks as complete
     Complete {
-        /// Task ID
-        id: String,
+        /// Task IDs (all in the same project)
+        #[arg(required = true, value_name = "ID")]
+        ids: Vec<String>,
 
         /// Project ID (uses default if not specified)
         #[arg(long, short)]
@@ -194,3 +199,10 @@ pub enum TaskCommands {
         project_name: Option<String>,
     },
 }
+
+impl TaskCommands {
+    /// Whether the command changes tasks in TickTick
+    pub fn is_mutating(&self) -> bool {
+        !matches!(self, TaskCommands::List { .. } | TaskCommands::Show { .. })
+    }
+}
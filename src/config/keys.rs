@@ -0,0 +1,690 @@
+//! Settings exposed by `tickrs config`
+//!
+//! Every key of [`Config`] is listed in [`KEYS`] with the type of its value
+//! and the environment variable overriding it, using dotted names for keys
+//! in sections (e.g. `retry.max_attempts`). [`ConfigFile`] edits the TOML
+//! document in place, so comments, formatting and keys unknown to this
+//! version survive a `set`.
+//!
+//! [`Layers`] holds what is applied over the config file: a directory-local
+//! `.tickrs.toml`, then environment variables. Command-line flags take
+//! precedence over all of them.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use toml_edit::{DocumentMut, Item, Value};
+
+use crate::config::local::{self, LocalFile};
+use crate::config::Config;
+use crate::constants::{
+    ENV_API_BASE_URL, ENV_CACHE, ENV_CACHE_TTL, ENV_CA_CERTS, ENV_CONNECT_TIMEOUT,
+    ENV_DEFAULT_PROJECT, ENV_DEFAULT_PROJECT_COLOR, ENV_HTTPS_PROXY, ENV_MAX_IN_FLIGHT,
+    ENV_OAUTH_BASE_URL, ENV_OAUTH_CALLBACK_HOST, ENV_OAUTH_CALLBACK_PORT,
+    ENV_OAUTH_CALLBACK_TIMEOUT, ENV_OAUTH_PKCE, ENV_PASSPHRASE_COMMAND, ENV_READ_TIMEOUT,
+    ENV_REGION, ENV_REQUESTS_PER_SECOND, ENV_RETRY_MAX_ATTEMPTS, ENV_RETRY_NON_IDEMPOTENT,
+    ENV_TASK_ALL_DAY, ENV_TASK_PRIORITY, ENV_TASK_TAGS, ENV_TASK_TIME, ENV_TASK_TIMEZONE,
+    ENV_TOKEN_COMMAND, ENV_TOKEN_STORAGE, ENV_TOKEN_STORE_COMMAND,
+};
+
+/// Type of a config value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    String,
+    Bool,
+    /// Non-negative integer
+    Integer,
+    /// List of strings, comma-separated
+    List,
+    /// List of paths, comma-separated on the command line and separated like
+    /// `PATH` in the environment
+    Paths,
+    /// One of the given strings
+    Choice(&'static [&'static str]),
+}
+
+/// A key that can be read and set with `tickrs config`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfigKey {
+    /// Dotted name, e.g. "retry.max_attempts"
+    pub name: &'static str,
+    /// Environment variable overriding the value
+    pub env: &'static str,
+    /// Type of the value
+    pub kind: ValueKind,
+}
+
+const fn key(name: &'static str, env: &'static str, kind: ValueKind) -> ConfigKey {
+    ConfigKey { name, env, kind }
+}
+
+/// All settable keys, in the order of `tickrs config list`
+pub const KEYS: &[ConfigKey] = &[
+    key("default_project_id", ENV_DEFAULT_PROJECT, ValueKind::String),
+    key(
+        "default_project_color",
+        ENV_DEFAULT_PROJECT_COLOR,
+        ValueKind::String,
+    ),
+    key(
+        "task_defaults.timezone",
+        ENV_TASK_TIMEZONE,
+        ValueKind::String,
+    ),
+    key(
+        "task_defaults.priority",
+        ENV_TASK_PRIORITY,
+        ValueKind::Choice(&["none", "low", "medium", "high"]),
+    ),
+    key("task_defaults.tags", ENV_TASK_TAGS, ValueKind::List),
+    key("task_defaults.all_day", ENV_TASK_ALL_DAY, ValueKind::Bool),
+    key("task_defaults.time", ENV_TASK_TIME, ValueKind::String),
+    key(
+        "region",
+        ENV_REGION,
+        ValueKind::Choice(&["ticktick", "dida365"]),
+    ),
+    key("api_base_url", ENV_API_BASE_URL, ValueKind::String),
+    key("oauth_base_url", ENV_OAUTH_BASE_URL, ValueKind::String),
+    key(
+        "retry.max_attempts",
+        ENV_RETRY_MAX_ATTEMPTS,
+        ValueKind::Integer,
+    ),
+    key(
+        "retry.retry_non_idempotent",
+        ENV_RETRY_NON_IDEMPOTENT,
+        ValueKind::Bool,
+    ),
+    key(
+        "http.connect_timeout_secs",
+        ENV_CONNECT_TIMEOUT,
+        ValueKind::Integer,
+    ),
+    key(
+        "http.read_timeout_secs",
+        ENV_READ_TIMEOUT,
+        ValueKind::Integer,
+    ),
+    key("http.https_proxy", ENV_HTTPS_PROXY, ValueKind::String),
+    key("http.ca_certs", ENV_CA_CERTS, ValueKind::Paths),
+    key(
+        "concurrency.max_in_flight",
+        ENV_MAX_IN_FLIGHT,
+        ValueKind::Integer,
+    ),
+    key(
+        "concurrency.requests_per_second",
+        ENV_REQUESTS_PER_SECOND,
+        ValueKind::Integer,
+    ),
+    key("cache.enabled", ENV_CACHE, ValueKind::Bool),
+    key("cache.ttl_secs", ENV_CACHE_TTL, ValueKind::Integer),
+    key(
+        "oauth.callback_host",
+        ENV_OAUTH_CALLBACK_HOST,
+        ValueKind::String,
+    ),
+    key(
+        "oauth.callback_port",
+        ENV_OAUTH_CALLBACK_PORT,
+        ValueKind::Integer,
+    ),
+    key(
+        "oauth.callback_timeout_secs",
+        ENV_OAUTH_CALLBACK_TIMEOUT,
+        ValueKind::Integer,
+    ),
+    key("oauth.pkce", ENV_OAUTH_PKCE, ValueKind::Bool),
+    key(
+        "token_storage.backend",
+        ENV_TOKEN_STORAGE,
+        ValueKind::Choice(&["file", "encrypted-file", "command", "keyring"]),
+    ),
+    key(
+        "token_storage.token_command",
+        ENV_TOKEN_COMMAND,
+        ValueKind::String,
+    ),
+    key(
+        "token_storage.token_store_command",
+        ENV_TOKEN_STORE_COMMAND,
+        ValueKind::String,
+    ),
+    key(
+        "token_storage.passphrase_command",
+        ENV_PASSPHRASE_COMMAND,
+        ValueKind::String,
+    ),
+];
+
+/// Find a key by its dotted name
+pub fn lookup(name: &str) -> Result<&'static ConfigKey> {
+    KEYS.iter().find(|key| key.name == name).ok_or_else(|| {
+        anyhow!(
+            "Unknown config key '{}'. Run 'tickrs config list' to see all keys",
+            name
+        )
+    })
+}
+
+impl ConfigKey {
+    /// Parse a value given on the command line
+    pub fn parse_value(&self, raw: &str) -> Result<Value> {
+        self.parse(raw, false).map(toml_value)
+    }
+
+    /// Parse the value of the key's environment variable
+    fn parse_env(&self, raw: &str) -> Result<JsonValue> {
+        self.parse(raw, true)
+            .map_err(|e| anyhow!("Invalid {}: {}", self.env, e))
+    }
+
+    fn parse(&self, raw: &str, from_env: bool) -> Result<JsonValue> {
+        let trimmed = raw.trim();
+        match self.kind {
+            ValueKind::String => Ok(JsonValue::from(trimmed)),
+            ValueKind::Bool => match (trimmed.to_lowercase().as_str(), from_env) {
+                ("true", _) => Ok(JsonValue::from(true)),
+                ("false", _) => Ok(JsonValue::from(false)),
+                // Accept the usual spellings of flags in the environment
+                ("1" | "yes" | "on", true) => Ok(JsonValue::from(true)),
+                ("0" | "no" | "off", true) => Ok(JsonValue::from(false)),
+                _ => bail!(
+                    "Invalid value for {}: expected true or false, got '{}'",
+                    self.name,
+                    raw
+                ),
+            },
+            ValueKind::Integer => trimmed.parse::<u32>().map(JsonValue::from).map_err(|_| {
+                anyhow!(
+                    "Invalid value for {}: expected a non-negative integer, got '{}'",
+                    self.name,
+                    raw
+                )
+            }),
+            ValueKind::Paths if from_env => Ok(JsonValue::Array(
+                std::env::split_paths(trimmed)
+                    .map(|path| JsonValue::from(path.to_string_lossy().into_owned()))
+                    .collect(),
+            )),
+            ValueKind::List | ValueKind::Paths => Ok(JsonValue::Array(
+                trimmed
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(JsonValue::from)
+                    .collect(),
+            )),
+            ValueKind::Choice(choices) => {
+                let value = trimmed.to_lowercase();
+                if !choices.contains(&value.as_str()) {
+                    bail!(
+                        "Invalid value for {}: expected one of {}, got '{}'",
+                        self.name,
+                        choices.join(", "),
+                        raw
+                    );
+                }
+                Ok(JsonValue::from(value))
+            }
+        }
+    }
+}
+
+/// Convert a parsed value (a string, bool, integer or list) to TOML
+fn toml_value(value: JsonValue) -> Value {
+    match value {
+        JsonValue::Bool(b) => Value::from(b),
+        JsonValue::Number(n) => Value::from(n.as_i64().unwrap_or_default()),
+        JsonValue::Array(items) => Value::Array(items.into_iter().map(toml_value).collect()),
+        JsonValue::String(s) => Value::from(s),
+        other => Value::from(other.to_string()),
+    }
+}
+
+/// Settings applied over the config file, lowest precedence first
+#[derive(Debug, Clone, Default)]
+pub struct Layers {
+    /// The `.tickrs.toml` that applies to the current directory
+    pub local: Option<LocalFile>,
+    /// Non-empty environment variables of config keys
+    pub env: HashMap<&'static str, String>,
+}
+
+impl Layers {
+    /// Layers for the current directory and environment
+    pub fn current() -> Result<Self> {
+        Ok(Self {
+            local: local::discover()?,
+            env: KEYS
+                .iter()
+                .filter_map(|key| {
+                    std::env::var(key.env)
+                        .ok()
+                        .filter(|value| !value.trim().is_empty())
+                        .map(|value| (key.env, value))
+                })
+                .collect(),
+        })
+    }
+
+    /// Apply the layers to settings read from a config file
+    pub fn apply(&self, mut config: Config) -> Result<Config> {
+        if let Some(local) = &self.local {
+            local.apply(&mut config);
+        }
+        if self.env.is_empty() {
+            return Ok(config);
+        }
+
+        let mut values = serde_json::to_value(&config)?;
+        for key in KEYS {
+            let Some(raw) = self.env.get(key.env) else {
+                continue;
+            };
+            let value = key.parse_env(raw)?;
+            match key.name.split_once('.') {
+                Some((section, name)) => values[section][name] = value,
+                None => values[key.name] = value,
+            }
+        }
+        let config: Config =
+            serde_json::from_value(values).map_err(|e| anyhow!("Invalid environment: {}", e))?;
+        config
+            .validate()
+            .map_err(|e| anyhow!("Invalid environment: {:#}", e))?;
+        Ok(config)
+    }
+
+    /// The layer setting `key`, if any
+    fn source(&self, key: &ConfigKey) -> Option<ValueSource> {
+        if self.env.contains_key(key.env) {
+            Some(ValueSource::Env)
+        } else if self
+            .local
+            .as_ref()
+            .is_some_and(|local| local.sets(key.name))
+        {
+            Some(ValueSource::Local)
+        } else {
+            None
+        }
+    }
+}
+
+/// Where the value of a setting comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueSource {
+    /// Built-in default
+    Default,
+    /// The profile's config file
+    File,
+    /// A directory-local `.tickrs.toml`
+    Local,
+    /// An environment variable
+    Env,
+}
+
+/// Value of a setting, as shown by `tickrs config get` and `list`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigEntry {
+    /// Dotted key name
+    pub key: String,
+    /// Environment variable overriding the value
+    pub env: String,
+    /// Effective value, `null` if unset
+    pub value: serde_json::Value,
+    /// Where the value comes from
+    pub source: ValueSource,
+}
+
+/// A config file, edited without losing comments or unknown keys
+pub struct ConfigFile {
+    path: PathBuf,
+    doc: DocumentMut,
+}
+
+impl ConfigFile {
+    /// Open the active profile's config file; a missing file is empty
+    pub fn open() -> Result<Self> {
+        Self::open_at(&Config::config_path()?)
+    }
+
+    /// Open a config file; a missing file is empty
+    pub fn open_at(path: &Path) -> Result<Self> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Failed to read config file: {}", path.display()))
+            }
+        };
+        let doc = contents
+            .parse::<DocumentMut>()
+            .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            doc,
+        })
+    }
+
+    /// Path of the file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn item(&self, key: &ConfigKey) -> Option<&Item> {
+        match key.name.split_once('.') {
+            Some((section, name)) => self.doc.get(section)?.get(name),
+            None => self.doc.get(key.name),
+        }
+        .filter(|item| !item.is_none())
+    }
+
+    /// Whether the file sets `key`
+    pub fn contains(&self, key: &ConfigKey) -> bool {
+        self.item(key).is_some()
+    }
+
+    /// Set `key`, keeping the comments around a previous value
+    pub fn set(&mut self, key: &ConfigKey, mut value: Value) -> Result<()> {
+        let (table, name) = match key.name.split_once('.') {
+            Some((section, name)) => {
+                let table = self
+                    .doc
+                    .entry(section)
+                    .or_insert_with(toml_edit::table)
+                    .as_table_like_mut()
+                    .with_context(|| {
+                        format!("'{}' in the config file is not a section", section)
+                    })?;
+                (table, name)
+            }
+            None => (
+                self.doc.as_table_mut() as &mut dyn toml_edit::TableLike,
+                key.name,
+            ),
+        };
+
+        // Replace the value in place, so the key keeps the comments above it
+        match table.get_mut(name) {
+            Some(Item::Value(previous)) => {
+                *value.decor_mut() = previous.decor().clone();
+                *previous = value;
+            }
+            _ => {
+                table.insert(name, Item::Value(value));
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove `key` from the file; returns whether it was set
+    pub fn unset(&mut self, key: &ConfigKey) -> bool {
+        let removed = match key.name.split_once('.') {
+            Some((section, name)) => self
+                .doc
+                .get_mut(section)
+                .and_then(Item::as_table_like_mut)
+                .and_then(|table| table.remove(name)),
+            None => self.doc.remove(key.name),
+        };
+        removed.is_some()
+    }
+
+    /// Configuration from this file, with defaults for unset keys
+    pub fn config(&self) -> Result<Config> {
+        toml::from_str(&self.doc.to_string())
+            .with_context(|| format!("Invalid config file: {}", self.path.display()))
+    }
+
+    /// Validate the settings and write the file
+    pub fn save(&self) -> Result<()> {
+        self.config()?.validate()?;
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create config directory: {}", parent.display())
+            })?;
+        }
+        fs::write(&self.path, self.doc.to_string())
+            .with_context(|| format!("Failed to write config file: {}", self.path.display()))
+    }
+
+    /// Effective value of `key`, with `layers` applied
+    pub fn entry(&self, key: &ConfigKey, layers: &Layers) -> Result<ConfigEntry> {
+        let values = serde_json::to_value(layers.apply(self.config()?)?)?;
+        Ok(self.entry_in(&values, key, layers))
+    }
+
+    /// Effective values of all keys, with `layers` applied
+    pub fn entries(&self, layers: &Layers) -> Result<Vec<ConfigEntry>> {
+        let values = serde_json::to_value(layers.apply(self.config()?)?)?;
+        Ok(KEYS
+            .iter()
+            .map(|key| self.entry_in(&values, key, layers))
+            .collect())
+    }
+
+    fn entry_in(&self, values: &JsonValue, key: &ConfigKey, layers: &Layers) -> ConfigEntry {
+        let value = key
+            .name
+            .split('.')
+            .try_fold(values, |value, name| value.get(name))
+            .cloned()
+            .unwrap_or(JsonValue::Null);
+        let source = layers.source(key).unwrap_or(if self.contains(key) {
+            ValueSource::File
+        } else {
+            ValueSource::Default
+        });
+        ConfigEntry {
+            key: key.name.to_string(),
+            env: key.env.to_string(),
+            value,
+            source,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_with(contents: &str) -> (tempfile::TempDir, ConfigFile) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, contents).unwrap();
+        let file = ConfigFile::open_at(&path).unwrap();
+        (dir, file)
+    }
+
+    #[test]
+    fn test_every_key_exists_in_config() {
+        let mut config = Config {
+            default_project_id: Some("p".to_string()),
+            api_base_url: Some("https://example.com".to_string()),
+            oauth_base_url: Some("https://example.com".to_string()),
+            ..Config::default()
+        };
+        config.task_defaults.values = crate::config::TaskDefaultValues {
+            timezone: Some("UTC".to_string()),
+            priority: Some(crate::models::Priority::High),
+            tags: vec!["t".to_string()],
+            all_day: Some(true),
+            time: Some("09:00".to_string()),
+        };
+        config.http.https_proxy = Some("http://proxy:3128".to_string());
+        config.http.ca_certs = vec![PathBuf::from("ca.pem")];
+        config.token_storage.token_command = Some("cmd".to_string());
+        config.token_storage.token_store_command = Some("cmd".to_string());
+        config.token_storage.passphrase_command = Some("cmd".to_string());
+        let values = serde_json::to_value(&config).unwrap();
+
+        for key in KEYS {
+            let value = key
+                .name
+                .split('.')
+                .try_fold(&values, |value, name| value.get(name));
+            assert!(value.is_some(), "{} is not a Config field", key.name);
+        }
+    }
+
+    #[test]
+    fn test_parse_value() {
+        let key = lookup("retry.max_attempts").unwrap();
+        assert_eq!(key.parse_value(" 5 ").unwrap().as_integer(), Some(5));
+        assert!(key.parse_value("-1").is_err());
+        assert!(key.parse_value("many").is_err());
+
+        let key = lookup("cache.enabled").unwrap();
+        assert_eq!(key.parse_value("false").unwrap().as_bool(), Some(false));
+        assert!(key.parse_value("yes").is_err());
+
+        let key = lookup("region").unwrap();
+        assert!(key.parse_value("dida365").is_ok());
+        assert!(key.parse_value("mars").is_err());
+
+        let key = lookup("http.ca_certs").unwrap();
+        let value = key.parse_value("a.pem, b.pem,").unwrap();
+        assert_eq!(value.as_array().unwrap().len(), 2);
+
+        assert!(lookup("nope").is_err());
+    }
+
+    #[test]
+    fn test_set_keeps_comments_and_unknown_keys() {
+        let (_dir, mut file) = file_with(
+            "# My settings\n\
+             default_project_color = \"#00FF00\" # green\n\
+             future_option = 1\n\
+             \n\
+             [retry]\n\
+             # Be patient\n\
+             max_attempts = 3\n",
+        );
+
+        let color = lookup("default_project_color").unwrap();
+        file.set(color, color.parse_value("#0000FF").unwrap())
+            .unwrap();
+        let attempts = lookup("retry.max_attempts").unwrap();
+        file.set(attempts, attempts.parse_value("5").unwrap())
+            .unwrap();
+        let ttl = lookup("cache.ttl_secs").unwrap();
+        file.set(ttl, ttl.parse_value("60").unwrap()).unwrap();
+        file.save().unwrap();
+
+        let contents = fs::read_to_string(file.path()).unwrap();
+        assert!(contents.contains("# My settings"));
+        assert!(contents.contains("default_project_color = \"#0000FF\" # green"));
+        assert!(contents.contains("future_option = 1"));
+        assert!(contents.contains("# Be patient\nmax_attempts = 5"));
+        assert!(contents.contains("[cache]\nttl_secs = 60"));
+
+        let config = ConfigFile::open_at(file.path()).unwrap().config().unwrap();
+        assert_eq!(config.retry.max_attempts, 5);
+        assert_eq!(config.cache.ttl_secs, 60);
+    }
+
+    #[test]
+    fn test_unset_and_entries() {
+        let (_dir, mut file) = file_with("region = \"dida365\"\n[cache]\nttl_secs = 5\n");
+
+        let entry = file
+            .entry(lookup("region").unwrap(), &Layers::default())
+            .unwrap();
+        assert_eq!(entry.value, "dida365");
+        assert_eq!(entry.source, ValueSource::File);
+
+        assert!(file.unset(lookup("region").unwrap()));
+        assert!(!file.unset(lookup("region").unwrap()));
+        assert!(file.unset(lookup("cache.ttl_secs").unwrap()));
+
+        let entries = file.entries(&Layers::default()).unwrap();
+        assert_eq!(entries.len(), KEYS.len());
+        let region = entries.iter().find(|e| e.key == "region").unwrap();
+        assert_eq!(region.value, "ticktick");
+        assert_eq!(region.source, ValueSource::Default);
+        let project = entries
+            .iter()
+            .find(|e| e.key == "default_project_id")
+            .unwrap();
+        assert!(project.value.is_null());
+    }
+
+    #[test]
+    fn test_entries_with_layers() {
+        let (_dir, file) = file_with("default_project_id = \"global\"\n");
+        let mut layers = Layers {
+            local: Some(LocalFile {
+                path: PathBuf::from(".tickrs.toml"),
+                config: crate::config::local::LocalConfig {
+                    default_project_id: Some("local".to_string()),
+                    ..Default::default()
+                },
+            }),
+            env: HashMap::new(),
+        };
+        let project = lookup("default_project_id").unwrap();
+
+        let entry = file.entry(project, &Layers::default()).unwrap();
+        assert_eq!(entry.value, "global");
+        assert_eq!(entry.source, ValueSource::File);
+
+        let entry = file.entry(project, &layers).unwrap();
+        assert_eq!(entry.value, "local");
+        assert_eq!(entry.source, ValueSource::Local);
+
+        // Environment variables override the local file
+        layers.env.insert(ENV_DEFAULT_PROJECT, "Work".to_string());
+        layers.env.insert(ENV_CACHE, "1".to_string());
+        layers.env.insert(ENV_CA_CERTS, "a.pem".to_string());
+        let entry = file.entry(project, &layers).unwrap();
+        assert_eq!(entry.value, "Work");
+        assert_eq!(entry.source, ValueSource::Env);
+        assert_eq!(entry.env, ENV_DEFAULT_PROJECT);
+
+        let config = layers.apply(file.config().unwrap()).unwrap();
+        assert!(config.cache.enabled);
+        assert_eq!(config.http.ca_certs, vec![PathBuf::from("a.pem")]);
+        assert_eq!(config.default_project_id.as_deref(), Some("Work"));
+    }
+
+    #[test]
+    fn test_invalid_environment_is_reported() {
+        let layers = Layers {
+            local: None,
+            env: HashMap::from([(ENV_RETRY_MAX_ATTEMPTS, "lots".to_string())]),
+        };
+        let err = layers.apply(Config::default()).unwrap_err();
+        assert!(format!("{:#}", err).contains(ENV_RETRY_MAX_ATTEMPTS));
+
+        let layers = Layers {
+            local: None,
+            env: HashMap::from([(ENV_DEFAULT_PROJECT_COLOR, "blue".to_string())]),
+        };
+        assert!(layers.apply(Config::default()).is_err());
+    }
+
+    #[test]
+    fn test_save_rejects_invalid_settings() {
+        let (_dir, mut file) = file_with("");
+        let color = lookup("default_project_color").unwrap();
+        file.set(color, color.parse_value("green").unwrap())
+            .unwrap();
+        assert!(file.save().is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut file = ConfigFile::open_at(&dir.path().join("config.toml")).unwrap();
+        let port = lookup("oauth.callback_port").unwrap();
+        file.set(port, port.parse_value("70000").unwrap()).unwrap();
+        assert!(file.save().is_err());
+        assert!(!file.path().exists());
+    }
+}
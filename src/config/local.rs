@@ -0,0 +1,181 @@
+//! Directory-local `.tickrs.toml` files
+//!
+//! A project can commit a `.tickrs.toml` to pick the profile, default
+//! project and default tags (`default_tags`, which sets `task_defaults.tags`)
+//! for commands run in its directory tree. The
+//! nearest file in the current directory or one of its parents is layered
+//! over the profile's config file.
+//!
+//! Only these keys are accepted: a checked-out repository must not be able
+//! to redirect requests to another server or run commands through the
+//! token storage settings.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::constants::LOCAL_CONFIG_FILE_NAME;
+
+/// Settings a `.tickrs.toml` may contain
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LocalConfig {
+    /// Profile to use (below `--profile` and `TICKRS_PROFILE`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Default project ID for commands
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_project_id: Option<String>,
+    /// Default color for new projects
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_project_color: Option<String>,
+    /// Tags added to new tasks, replacing `task_defaults.tags`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_tags: Option<Vec<String>>,
+}
+
+/// A `.tickrs.toml` and its settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalFile {
+    pub path: PathBuf,
+    pub config: LocalConfig,
+}
+
+impl LocalFile {
+    /// Read and check a local config file
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let config: LocalConfig = toml::from_str(&contents)
+            .with_context(|| format!("Invalid local config file: {}", path.display()))?;
+        let file = Self {
+            path: path.to_path_buf(),
+            config,
+        };
+
+        if let Some(name) = &file.config.profile {
+            crate::config::profile::validate_name(name)
+                .with_context(|| format!("Invalid profile in {}", path.display()))?;
+        }
+        let mut check = Config::default();
+        file.apply(&mut check);
+        check
+            .validate()
+            .with_context(|| format!("Invalid local config file: {}", path.display()))?;
+
+        Ok(file)
+    }
+
+    /// Override the settings of `config` that this file sets
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(id) = &self.config.default_project_id {
+            config.default_project_id = Some(id.clone());
+        }
+        if let Some(color) = &self.config.default_project_color {
+            config.default_project_color = color.clone();
+        }
+        if let Some(tags) = &self.config.default_tags {
+            config.task_defaults.values.tags = tags.clone();
+        }
+    }
+
+    /// Whether this file sets the config key `name`
+    pub fn sets(&self, name: &str) -> bool {
+        match name {
+            "default_project_id" => self.config.default_project_id.is_some(),
+            "default_project_color" => self.config.default_project_color.is_some(),
+            "task_defaults.tags" => self.config.default_tags.is_some(),
+            _ => false,
+        }
+    }
+}
+
+/// Find the nearest `.tickrs.toml` in `dir` or one of its parents
+pub fn find(dir: &Path) -> Result<Option<LocalFile>> {
+    for dir in dir.ancestors() {
+        let path = dir.join(LOCAL_CONFIG_FILE_NAME);
+        if path.is_file() {
+            return LocalFile::load(&path).map(Some);
+        }
+    }
+    Ok(None)
+}
+
+/// Find the `.tickrs.toml` that applies to the current directory
+pub fn discover() -> Result<Option<LocalFile>> {
+    match std::env::current_dir() {
+        Ok(dir) => find(&dir),
+        // A deleted working directory has no local config
+        Err(_) => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_searches_parent_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("src/deep");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(
+            dir.path().join(LOCAL_CONFIG_FILE_NAME),
+            "profile = \"work\"\ndefault_tags = [\"repo\"]\n",
+        )
+        .unwrap();
+
+        let file = find(&nested).unwrap().unwrap();
+        assert_eq!(file.path, dir.path().join(LOCAL_CONFIG_FILE_NAME));
+        assert_eq!(file.config.profile.as_deref(), Some("work"));
+
+        // The nearest file wins
+        fs::write(
+            nested.join(LOCAL_CONFIG_FILE_NAME),
+            "default_project_id = \"p1\"\n",
+        )
+        .unwrap();
+        let file = find(&nested).unwrap().unwrap();
+        assert_eq!(file.config.default_project_id.as_deref(), Some("p1"));
+        assert!(file.config.profile.is_none());
+    }
+
+    #[test]
+    fn test_apply_overrides_only_set_keys() {
+        let file = LocalFile {
+            path: PathBuf::from(LOCAL_CONFIG_FILE_NAME),
+            config: LocalConfig {
+                default_tags: Some(vec!["repo".to_string()]),
+                ..LocalConfig::default()
+            },
+        };
+        let mut config = Config {
+            default_project_id: Some("global".to_string()),
+            ..Config::default()
+        };
+        file.apply(&mut config);
+
+        assert_eq!(config.default_project_id.as_deref(), Some("global"));
+        assert_eq!(config.task_defaults.values.tags, vec!["repo".to_string()]);
+        assert!(file.sets("task_defaults.tags"));
+        assert!(!file.sets("default_project_id"));
+    }
+
+    #[test]
+    fn test_load_rejects_other_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(LOCAL_CONFIG_FILE_NAME);
+
+        fs::write(&path, "api_base_url = \"https://evil.example\"\n").unwrap();
+        assert!(LocalFile::load(&path).is_err());
+
+        fs::write(&path, "default_project_color = \"blue\"\n").unwrap();
+        assert!(LocalFile::load(&path).is_err());
+
+        fs::write(&path, "profile = \"../other\"\n").unwrap();
+        assert!(LocalFile::load(&path).is_err());
+    }
+}
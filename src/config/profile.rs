@@ -0,0 +1,295 @@
+//! Named account profiles
+//!
+//! Every profile has its own config file, token and response cache. The
+//! `default` profile uses the original locations (`~/.config/tickrs/config.toml`
+//! and `~/.local/share/tickrs/token`); other profiles live in
+//! `profiles/<name>` below both directories.
+//!
+//! The active profile is taken from `--profile`, then `TICKRS_PROFILE`, then
+//! a directory-local `.tickrs.toml`, then the profile selected with
+//! `tickrs profile use`.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{local, secrets, Config};
+use crate::constants::{
+    ACTIVE_PROFILE_FILE_NAME, APP_DIR_NAME, CONFIG_FILE_NAME, ENV_PROFILE, PROFILES_DIR_NAME,
+};
+
+/// Name of the profile using the original file locations
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Set by `--profile`, takes precedence over `TICKRS_PROFILE` and `profile use`
+static ACTIVE_OVERRIDE: RwLock<Option<String>> = RwLock::new(None);
+
+/// Summary of a profile, as shown by `tickrs profile list`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileInfo {
+    /// Profile name
+    pub name: String,
+    /// Whether this is the active profile
+    pub active: bool,
+    /// Whether a token is stored for this profile
+    pub authenticated: bool,
+    /// Default project of this profile
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_project_id: Option<String>,
+}
+
+/// Use `name` as the active profile for this process (`--profile`)
+pub fn set_active(name: Option<&str>) -> Result<()> {
+    if let Some(name) = name {
+        validate_name(name)?;
+    }
+    *ACTIVE_OVERRIDE.write().unwrap_or_else(|e| e.into_inner()) = name.map(str::to_string);
+    Ok(())
+}
+
+/// Name of the active profile
+pub fn active() -> Result<String> {
+    if let Some(name) = ACTIVE_OVERRIDE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+    {
+        return Ok(name);
+    }
+
+    if let Ok(name) = std::env::var(ENV_PROFILE) {
+        let name = name.trim();
+        if !name.is_empty() {
+            validate_name(name).with_context(|| format!("Invalid {}", ENV_PROFILE))?;
+            return Ok(name.to_string());
+        }
+    }
+
+    if let Some(name) = local::discover()?.and_then(|file| file.config.profile) {
+        return Ok(name);
+    }
+
+    Ok(selected()?.unwrap_or_else(|| DEFAULT_PROFILE.to_string()))
+}
+
+/// Profile selected with `tickrs profile use`, if any
+pub fn selected() -> Result<Option<String>> {
+    let path = base_config_dir()?.join(ACTIVE_PROFILE_FILE_NAME);
+    let name = match fs::read_to_string(&path) {
+        Ok(contents) => contents.trim().to_string(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read {}", path.display()));
+        }
+    };
+
+    // Ignore a selection that was edited by hand or whose profile is gone
+    if validate_name(&name).is_err() || !exists(&name)? {
+        return Ok(None);
+    }
+    Ok(Some(name))
+}
+
+/// Make `name` the active profile for future invocations (`tickrs profile use`)
+pub fn select(name: &str) -> Result<()> {
+    require(name)?;
+    let dir = base_config_dir()?;
+    let path = dir.join(ACTIVE_PROFILE_FILE_NAME);
+
+    if name == DEFAULT_PROFILE {
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                return Err(e).with_context(|| format!("Failed to delete {}", path.display()));
+            }
+            _ => return Ok(()),
+        }
+    }
+
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create config directory: {}", dir.display()))?;
+    fs::write(&path, format!("{}\n", name))
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Check that `name` can be used as a profile (and directory) name
+pub fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.len() > 64 {
+        bail!("Profile name must be between 1 and 64 characters");
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!(
+            "Invalid profile name '{}': use letters, digits, '-' and '_' only",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// Directory holding the config file of a profile
+pub fn config_dir(name: &str) -> Result<PathBuf> {
+    profile_dir(base_config_dir()?, name)
+}
+
+/// Directory holding the token and cache of a profile
+pub fn data_dir(name: &str) -> Result<PathBuf> {
+    profile_dir(base_data_dir()?, name)
+}
+
+fn profile_dir(base: PathBuf, name: &str) -> Result<PathBuf> {
+    if name == DEFAULT_PROFILE {
+        return Ok(base);
+    }
+    validate_name(name)?;
+    Ok(base.join(PROFILES_DIR_NAME).join(name))
+}
+
+fn base_config_dir() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir().context("Could not determine config directory")?;
+    Ok(config_dir.join(APP_DIR_NAME))
+}
+
+fn base_data_dir() -> Result<PathBuf> {
+    let data_dir = dirs::data_local_dir().context("Could not determine data directory")?;
+    Ok(data_dir.join(APP_DIR_NAME))
+}
+
+/// Whether a profile exists. The default profile always exists.
+pub fn exists(name: &str) -> Result<bool> {
+    if name == DEFAULT_PROFILE {
+        return Ok(true);
+    }
+    Ok(config_dir(name)?.is_dir())
+}
+
+/// Fail with a hint to create the profile if it doesn't exist
+pub fn require(name: &str) -> Result<()> {
+    if !exists(name)? {
+        bail!(
+            "Profile '{}' does not exist. Create it with 'tickrs profile add {}'",
+            name,
+            name
+        );
+    }
+    Ok(())
+}
+
+/// Names of all profiles, `default` first
+pub fn list() -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    let dir = base_config_dir()?.join(PROFILES_DIR_NAME);
+    if let Ok(entries) = fs::read_dir(&dir) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if entry.path().is_dir() && name != DEFAULT_PROFILE && validate_name(&name).is_ok() {
+                names.push(name);
+            }
+        }
+    }
+    names.sort();
+    names.insert(0, DEFAULT_PROFILE.to_string());
+    Ok(names)
+}
+
+/// Summary of a profile
+pub fn info(name: &str) -> Result<ProfileInfo> {
+    let config_path = config_dir(name)?.join(CONFIG_FILE_NAME);
+    let config = if config_path.exists() {
+        Config::load_from(&config_path)?
+    } else {
+        Config::default()
+    };
+    let store = secrets::open(&config.token_storage, &data_dir(name)?, name)?;
+
+    Ok(ProfileInfo {
+        name: name.to_string(),
+        active: active()? == name,
+        authenticated: store.exists()?,
+        default_project_id: config.default_project_id,
+    })
+}
+
+/// Create a profile with a default config file
+pub fn create(name: &str) -> Result<()> {
+    validate_name(name)?;
+    if exists(name)? {
+        bail!("Profile '{}' already exists", name);
+    }
+
+    let dir = config_dir(name)?;
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create profile directory: {}", dir.display()))?;
+    Config::default().save_to(&dir.join(CONFIG_FILE_NAME))
+}
+
+/// Delete a profile with its config, token and cache
+pub fn remove(name: &str) -> Result<()> {
+    if name == DEFAULT_PROFILE {
+        bail!("The default profile can't be removed; use 'tickrs reset' to clear it");
+    }
+    require(name)?;
+
+    let was_selected = selected()?.as_deref() == Some(name);
+    for dir in [config_dir(name)?, data_dir(name)?] {
+        match fs::remove_dir_all(&dir) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                return Err(e).with_context(|| format!("Failed to delete {}", dir.display()));
+            }
+            _ => {}
+        }
+    }
+
+    if was_selected {
+        select(DEFAULT_PROFILE)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("work").is_ok());
+        assert!(validate_name("team-shared_2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("../etc").is_err());
+        assert!(validate_name("my profile").is_err());
+        assert!(validate_name(&"a".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn test_default_profile_uses_original_locations() {
+        let config = config_dir(DEFAULT_PROFILE).unwrap();
+        let data = data_dir(DEFAULT_PROFILE).unwrap();
+        assert!(config.ends_with(APP_DIR_NAME));
+        assert!(data.ends_with(APP_DIR_NAME));
+        assert!(exists(DEFAULT_PROFILE).unwrap());
+    }
+
+    #[test]
+    fn test_named_profile_directories() {
+        let config = config_dir("work").unwrap();
+        let data = data_dir("work").unwrap();
+        assert_eq!(
+            config,
+            config_dir(DEFAULT_PROFILE)
+                .unwrap()
+                .join(PROFILES_DIR_NAME)
+                .join("work")
+        );
+        assert!(data.ends_with(PathBuf::from(PROFILES_DIR_NAME).join("work")));
+        assert!(config_dir("../work").is_err());
+    }
+
+    #[test]
+    fn test_default_profile_cannot_be_removed() {
+        assert!(remove(DEFAULT_PROFILE).is_err());
+    }
+}
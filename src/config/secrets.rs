@@ -0,0 +1,621 @@
+//! Storage backends for OAuth tokens
+//!
+//! [`TokenStorage`](crate::config::TokenStorage) keeps the serialized
+//! [`TokenSet`](crate::config::TokenSet) in one of these backends, selected
+//! in the `[token_storage]` config section:
+//!
+//! - `file` (default): plain JSON file with 0600 permissions
+//! - `encrypted-file`: file encrypted with ChaCha20-Poly1305, using a key
+//!   derived with Argon2id from a passphrase (`TICKRS_TOKEN_PASSPHRASE` or
+//!   the output of `passphrase_command`)
+//! - `command`: the output of `token_command` (e.g. `pass show ticktick`),
+//!   optionally saved with `token_store_command`
+//! - `keyring`: the system keyring, if built with the `keyring` feature
+
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::ENV_TOKEN_PASSPHRASE;
+use crate::utils::logging;
+
+/// Where tokens are stored
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SecretBackend {
+    /// Plain JSON file with 0600 permissions
+    #[default]
+    File,
+    /// Passphrase-encrypted file
+    EncryptedFile,
+    /// External command such as a password manager
+    Command,
+    /// System keyring (requires the `keyring` feature)
+    Keyring,
+}
+
+impl fmt::Display for SecretBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SecretBackend::File => write!(f, "file"),
+            SecretBackend::EncryptedFile => write!(f, "encrypted-file"),
+            SecretBackend::Command => write!(f, "command"),
+            SecretBackend::Keyring => write!(f, "keyring"),
+        }
+    }
+}
+
+/// Token storage settings (`[token_storage]` section)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenStorageConfig {
+    /// Backend holding the tokens
+    #[serde(default)]
+    pub backend: SecretBackend,
+    /// Command printing the stored tokens (`command` backend)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_command: Option<String>,
+    /// Command receiving tokens to store on stdin (`command` backend);
+    /// without it, the backend is read-only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_store_command: Option<String>,
+    /// Command printing the passphrase (`encrypted-file` backend), used when
+    /// `TICKRS_TOKEN_PASSPHRASE` is not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub passphrase_command: Option<String>,
+}
+
+/// A place to keep one secret
+pub trait SecretStore {
+    /// Read the secret, `None` if nothing is stored
+    fn load(&self) -> Result<Option<String>>;
+    /// Store the secret, replacing any previous one
+    fn save(&self, secret: &str) -> Result<()>;
+    /// Remove the secret if present
+    fn delete(&self) -> Result<()>;
+    /// Whether a secret is stored, without decrypting or running commands if possible
+    fn exists(&self) -> Result<bool>;
+    /// Human-readable location (path, command or keyring entry)
+    fn location(&self) -> String;
+    /// File holding the secret, for backends that use one
+    fn path(&self) -> Option<&Path> {
+        None
+    }
+}
+
+/// Open the store configured in `config` for a profile.
+///
+/// File backends keep their files in `data_dir`; the keyring entry is named
+/// after the profile.
+pub fn open(
+    config: &TokenStorageConfig,
+    data_dir: &Path,
+    profile: &str,
+) -> Result<Box<dyn SecretStore>> {
+    use crate::constants::{ENCRYPTED_TOKEN_FILE_NAME, TOKEN_FILE_NAME};
+
+    match config.backend {
+        SecretBackend::File => Ok(Box::new(PlainFileStore {
+            path: data_dir.join(TOKEN_FILE_NAME),
+        })),
+        SecretBackend::EncryptedFile => Ok(Box::new(EncryptedFileStore {
+            path: data_dir.join(ENCRYPTED_TOKEN_FILE_NAME),
+            passphrase_command: config.passphrase_command.clone(),
+        })),
+        SecretBackend::Command => {
+            let read = config
+                .token_command
+                .clone()
+                .filter(|command| !command.trim().is_empty())
+                .ok_or_else(|| {
+                    anyhow!("The 'command' token storage backend needs token_storage.token_command")
+                })?;
+            Ok(Box::new(CommandStore {
+                read,
+                write: config.token_store_command.clone(),
+            }))
+        }
+        SecretBackend::Keyring => open_keyring(profile),
+    }
+}
+
+#[cfg(feature = "keyring")]
+fn open_keyring(profile: &str) -> Result<Box<dyn SecretStore>> {
+    Ok(Box::new(KeyringStore {
+        service: crate::constants::APP_DIR_NAME.to_string(),
+        user: profile.to_string(),
+    }))
+}
+
+#[cfg(not(feature = "keyring"))]
+fn open_keyring(_profile: &str) -> Result<Box<dyn SecretStore>> {
+    bail!("tickrs was built without keyring support; rebuild it with '--features keyring' or choose another token_storage.backend")
+}
+
+/// Write `contents` to `path` with 0600 permissions, creating parent directories
+fn write_private_file(path: &Path, contents: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create data directory: {}", parent.display()))?;
+    }
+
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("Failed to create token file: {}", path.display()))?;
+    file.write_all(contents)
+        .with_context(|| "Failed to write token file")?;
+
+    // Files created by earlier versions may have wider permissions
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+            .with_context(|| "Failed to set token file permissions")?;
+    }
+    Ok(())
+}
+
+fn delete_file(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(e).with_context(|| format!("Failed to delete token file: {}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Plain file with 0600 permissions
+struct PlainFileStore {
+    path: PathBuf,
+}
+
+impl SecretStore for PlainFileStore {
+    fn load(&self) -> Result<Option<String>> {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) => Ok(Some(contents)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e)
+                .with_context(|| format!("Failed to read token file: {}", self.path.display())),
+        }
+    }
+
+    fn save(&self, secret: &str) -> Result<()> {
+        write_private_file(&self.path, secret.as_bytes())
+    }
+
+    fn delete(&self) -> Result<()> {
+        delete_file(&self.path)
+    }
+
+    fn exists(&self) -> Result<bool> {
+        Ok(self.path.exists())
+    }
+
+    fn location(&self) -> String {
+        self.path.display().to_string()
+    }
+
+    fn path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+}
+
+/// Contents of an encrypted token file
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedFile {
+    version: u32,
+    /// Key derivation function ("argon2id")
+    kdf: String,
+    /// Base64 salt for the key derivation
+    salt: String,
+    /// Base64 ChaCha20-Poly1305 nonce
+    nonce: String,
+    /// Base64 ciphertext including the authentication tag
+    ciphertext: String,
+}
+
+const ENCRYPTED_FILE_VERSION: u32 = 1;
+const KDF_ARGON2ID: &str = "argon2id";
+const SALT_LEN: usize = 16;
+
+/// File encrypted with a key derived from a passphrase
+struct EncryptedFileStore {
+    path: PathBuf,
+    passphrase_command: Option<String>,
+}
+
+impl EncryptedFileStore {
+    fn passphrase(&self) -> Result<String> {
+        if let Ok(passphrase) = std::env::var(ENV_TOKEN_PASSPHRASE) {
+            if !passphrase.is_empty() {
+                logging::register_secret(&passphrase);
+                return Ok(passphrase);
+            }
+        }
+
+        if let Some(command) = &self.passphrase_command {
+            let passphrase = run_read_command(command)
+                .context("Failed to get the token passphrase from passphrase_command")?;
+            if !passphrase.is_empty() {
+                logging::register_secret(&passphrase);
+                return Ok(passphrase);
+            }
+        }
+
+        bail!(
+            "The encrypted token file needs a passphrase: set {} or token_storage.passphrase_command",
+            ENV_TOKEN_PASSPHRASE
+        )
+    }
+}
+
+/// Derive a 256-bit key from a passphrase with Argon2id
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key> {
+    let mut key = Key::default();
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow!("Failed to derive token encryption key: {}", e))?;
+    Ok(key)
+}
+
+fn encrypt(secret: &str, passphrase: &str) -> Result<EncryptedFile> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, secret.as_bytes())
+        .map_err(|_| anyhow!("Failed to encrypt tokens"))?;
+
+    Ok(EncryptedFile {
+        version: ENCRYPTED_FILE_VERSION,
+        kdf: KDF_ARGON2ID.to_string(),
+        salt: BASE64.encode(salt),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    })
+}
+
+fn decrypt(file: &EncryptedFile, passphrase: &str) -> Result<String> {
+    if file.version != ENCRYPTED_FILE_VERSION || file.kdf != KDF_ARGON2ID {
+        bail!(
+            "Unsupported encrypted token file (version {}, kdf {})",
+            file.version,
+            file.kdf
+        );
+    }
+
+    let decode = |field: &str, value: &str| {
+        BASE64
+            .decode(value)
+            .with_context(|| format!("Invalid {} in encrypted token file", field))
+    };
+    let salt = decode("salt", &file.salt)?;
+    let nonce = decode("nonce", &file.nonce)?;
+    let ciphertext = decode("ciphertext", &file.ciphertext)?;
+    if nonce.len() != 12 {
+        bail!("Invalid nonce in encrypted token file");
+    }
+
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+        .map_err(|_| {
+            anyhow!("Failed to decrypt the token file: wrong passphrase or corrupted file")
+        })?;
+    String::from_utf8(plaintext).context("Decrypted tokens are not valid UTF-8")
+}
+
+impl SecretStore for EncryptedFileStore {
+    fn load(&self) -> Result<Option<String>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Failed to read token file: {}", self.path.display()))
+            }
+        };
+        let file: EncryptedFile =
+            serde_json::from_str(&contents).context("Failed to parse encrypted token file")?;
+        let secret = decrypt(&file, &self.passphrase()?)?;
+        Ok(Some(secret))
+    }
+
+    fn save(&self, secret: &str) -> Result<()> {
+        let file = encrypt(secret, &self.passphrase()?)?;
+        write_private_file(&self.path, &serde_json::to_vec_pretty(&file)?)
+    }
+
+    fn delete(&self) -> Result<()> {
+        delete_file(&self.path)
+    }
+
+    fn exists(&self) -> Result<bool> {
+        Ok(self.path.exists())
+    }
+
+    fn location(&self) -> String {
+        self.path.display().to_string()
+    }
+
+    fn path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+}
+
+/// External commands reading and (optionally) writing the secret
+struct CommandStore {
+    read: String,
+    write: Option<String>,
+}
+
+impl SecretStore for CommandStore {
+    fn load(&self) -> Result<Option<String>> {
+        let output = run_read_command(&self.read).context("token_command failed")?;
+        Ok((!output.is_empty()).then_some(output))
+    }
+
+    fn save(&self, secret: &str) -> Result<()> {
+        let command = self.write.as_deref().ok_or_else(|| {
+            anyhow!("Tokens can't be saved: set token_storage.token_store_command, or store them yourself where token_command reads them")
+        })?;
+        run_write_command(command, secret).context("token_store_command failed")
+    }
+
+    fn delete(&self) -> Result<()> {
+        // The secret is managed outside of tickrs
+        Ok(())
+    }
+
+    fn exists(&self) -> Result<bool> {
+        Ok(self.load()?.is_some())
+    }
+
+    fn location(&self) -> String {
+        format!("command: {}", self.read)
+    }
+}
+
+/// Shell command running `command`
+fn shell(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+/// Run a command and return its trimmed stdout. Stdin and stderr stay
+/// attached to the terminal, so password managers can prompt.
+fn run_read_command(command: &str) -> Result<String> {
+    let output = shell(command)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .with_context(|| format!("Failed to run '{}'", command))?;
+    if !output.status.success() {
+        bail!("'{}' exited with {}", command, output.status);
+    }
+    let stdout = String::from_utf8(output.stdout)
+        .with_context(|| format!("Output of '{}' is not valid UTF-8", command))?;
+    Ok(stdout.trim().to_string())
+}
+
+/// Run a command with `input` on stdin
+fn run_write_command(command: &str, input: &str) -> Result<()> {
+    let mut child = shell(command)
+        .stdin(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run '{}'", command))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        bail!("'{}' exited with {}", command, status);
+    }
+    Ok(())
+}
+
+/// Entry in the system keyring
+#[cfg(feature = "keyring")]
+struct KeyringStore {
+    service: String,
+    user: String,
+}
+
+#[cfg(feature = "keyring")]
+impl KeyringStore {
+    fn entry(&self) -> Result<keyring::Entry> {
+        keyring::Entry::new(&self.service, &self.user).context("Failed to open keyring entry")
+    }
+}
+
+#[cfg(feature = "keyring")]
+impl SecretStore for KeyringStore {
+    fn load(&self) -> Result<Option<String>> {
+        match self.entry()?.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e).context("Failed to read tokens from the keyring"),
+        }
+    }
+
+    fn save(&self, secret: &str) -> Result<()> {
+        self.entry()?
+            .set_password(secret)
+            .context("Failed to store tokens in the keyring")
+    }
+
+    fn delete(&self) -> Result<()> {
+        match self.entry()?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e).context("Failed to delete tokens from the keyring"),
+        }
+    }
+
+    fn exists(&self) -> Result<bool> {
+        Ok(self.load()?.is_some())
+    }
+
+    fn location(&self) -> String {
+        format!("keyring: {}/{}", self.service, self.user)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> tempfile::TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    #[test]
+    fn test_backend_serialization() {
+        let config: TokenStorageConfig =
+            toml::from_str("backend = \"encrypted-file\"\npassphrase_command = \"echo pw\"\n")
+                .unwrap();
+        assert_eq!(config.backend, SecretBackend::EncryptedFile);
+        assert_eq!(config.passphrase_command.as_deref(), Some("echo pw"));
+
+        let config: TokenStorageConfig = toml::from_str("").unwrap();
+        assert_eq!(config.backend, SecretBackend::File);
+        assert_eq!(SecretBackend::EncryptedFile.to_string(), "encrypted-file");
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() {
+        let file = encrypt(r#"{"access_token":"abc"}"#, "correct horse").unwrap();
+        assert!(!file.ciphertext.contains("abc"));
+        assert_eq!(
+            decrypt(&file, "correct horse").unwrap(),
+            r#"{"access_token":"abc"}"#
+        );
+
+        let err = decrypt(&file, "wrong").unwrap_err();
+        assert!(err.to_string().contains("wrong passphrase"));
+    }
+
+    #[test]
+    fn test_encryption_uses_fresh_salt_and_nonce() {
+        let a = encrypt("secret", "pw").unwrap();
+        let b = encrypt("secret", "pw").unwrap();
+        assert_ne!(a.salt, b.salt);
+        assert_ne!(a.nonce, b.nonce);
+        assert_ne!(a.ciphertext, b.ciphertext);
+    }
+
+    #[test]
+    fn test_plain_file_store() {
+        let dir = temp_dir();
+        let store = open(&TokenStorageConfig::default(), dir.path(), "default").unwrap();
+        assert!(!store.exists().unwrap());
+        assert_eq!(store.load().unwrap(), None);
+
+        store.save("secret").unwrap();
+        assert!(store.exists().unwrap());
+        assert_eq!(store.load().unwrap().as_deref(), Some("secret"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(dir.path().join("token"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        store.delete().unwrap();
+        assert!(!store.exists().unwrap());
+        store.delete().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_encrypted_file_store_with_passphrase_command() {
+        let dir = temp_dir();
+        let config = TokenStorageConfig {
+            backend: SecretBackend::EncryptedFile,
+            passphrase_command: Some("echo file-store-passphrase".to_string()),
+            ..TokenStorageConfig::default()
+        };
+        let store = open(&config, dir.path(), "default").unwrap();
+
+        store.save("secret-tokens").unwrap();
+        let contents = fs::read_to_string(dir.path().join("token.enc")).unwrap();
+        assert!(!contents.contains("secret-tokens"));
+        assert_eq!(store.load().unwrap().as_deref(), Some("secret-tokens"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_store() {
+        let dir = temp_dir();
+        let file = dir.path().join("external");
+        let config = TokenStorageConfig {
+            backend: SecretBackend::Command,
+            token_command: Some(format!("cat {} 2>/dev/null || true", file.display())),
+            token_store_command: Some(format!("cat > {}", file.display())),
+            ..TokenStorageConfig::default()
+        };
+        let store = open(&config, dir.path(), "default").unwrap();
+        assert_eq!(store.load().unwrap(), None);
+
+        store.save("from-command\n").unwrap();
+        assert_eq!(store.load().unwrap().as_deref(), Some("from-command"));
+        assert!(store.location().starts_with("command: cat"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_store_failures() {
+        let dir = temp_dir();
+        let config = TokenStorageConfig {
+            backend: SecretBackend::Command,
+            token_command: Some("exit 3".to_string()),
+            ..TokenStorageConfig::default()
+        };
+        let store = open(&config, dir.path(), "default").unwrap();
+        assert!(store.load().is_err());
+        assert!(store.save("tokens").is_err());
+
+        let config = TokenStorageConfig {
+            backend: SecretBackend::Command,
+            ..TokenStorageConfig::default()
+        };
+        assert!(open(&config, dir.path(), "default").is_err());
+    }
+
+    #[cfg(not(feature = "keyring"))]
+    #[test]
+    fn test_keyring_requires_feature() {
+        let config = TokenStorageConfig {
+            backend: SecretBackend::Keyring,
+            ..TokenStorageConfig::default()
+        };
+        let err = open(&config, Path::new("/tmp"), "default").err().unwrap();
+        assert!(err.to_string().contains("--features keyring"));
+    }
+}
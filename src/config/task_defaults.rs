@@ -0,0 +1,255 @@
+//! Defaults for new tasks (`[task_defaults]` section)
+//!
+//! ```toml
+//! [task_defaults]
+//! timezone = "Europe/Amsterdam"
+//! priority = "medium"
+//! time = "09:00"
+//!
+//! [task_defaults.projects.Work]
+//! tags = ["work"]
+//! priority = "high"
+//! ```
+//!
+//! Entries in `projects` are keyed by project name or ID and override the
+//! section's values for tasks in that project; their tags are added to the
+//! section's tags. Explicit command-line flags and [`TaskBuilder`] setters
+//! take precedence over all defaults.
+//!
+//! [`TaskBuilder`]: crate::models::task::TaskBuilder
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, bail, Result};
+use chrono::NaiveTime;
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::models::task::add_tags;
+use crate::models::Priority;
+
+/// Default values for new tasks
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskDefaultValues {
+    /// Timezone of new tasks (IANA name, e.g. "Europe/Amsterdam")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Priority of new tasks
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "priority_name"
+    )]
+    pub priority: Option<Priority>,
+    /// Tags added to new tasks
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Whether new tasks are all-day tasks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub all_day: Option<bool>,
+    /// Time of day ("HH:MM") for dates given without a time, like "tomorrow"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>,
+}
+
+impl TaskDefaultValues {
+    /// Check the timezone and time of day, naming keys below `section`
+    fn validate(&self, section: &str) -> Result<()> {
+        if let Some(timezone) = &self.timezone {
+            if timezone.parse::<Tz>().is_err() {
+                bail!("Invalid {}.timezone '{}'", section, timezone);
+            }
+        }
+        if let Some(time) = &self.time {
+            parse_time(time).map_err(|e| anyhow!("Invalid {}.{}", section, e))?;
+        }
+        Ok(())
+    }
+
+    /// The default time of day, if set
+    pub fn time_of_day(&self) -> Option<NaiveTime> {
+        self.time.as_deref().and_then(|time| parse_time(time).ok())
+    }
+
+    /// These values, with unset ones taken from `fallback` and its tags added
+    pub fn or(&self, fallback: &TaskDefaultValues) -> TaskDefaultValues {
+        let mut tags = fallback.tags.clone();
+        add_tags(&mut tags, self.tags.iter().cloned());
+        TaskDefaultValues {
+            timezone: self.timezone.clone().or_else(|| fallback.timezone.clone()),
+            priority: self.priority.or(fallback.priority),
+            tags,
+            all_day: self.all_day.or(fallback.all_day),
+            time: self.time.clone().or_else(|| fallback.time.clone()),
+        }
+    }
+}
+
+/// The `[task_defaults]` section
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskDefaults {
+    /// Defaults for tasks in all projects
+    #[serde(flatten)]
+    pub values: TaskDefaultValues,
+    /// Overrides for tasks in a project, keyed by project name or ID
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub projects: BTreeMap<String, TaskDefaultValues>,
+}
+
+impl TaskDefaults {
+    /// Check the section and every project entry
+    pub fn validate(&self) -> Result<()> {
+        self.values.validate("task_defaults")?;
+        for (project, values) in &self.projects {
+            values.validate(&format!("task_defaults.projects.\"{}\"", project))?;
+        }
+        Ok(())
+    }
+
+    /// Whether the project's name is needed to find its entry, i.e. there
+    /// are project entries but none keyed by `project_id`
+    pub fn needs_project_name(&self, project_id: &str) -> bool {
+        !self.projects.is_empty() && !self.projects.contains_key(project_id)
+    }
+
+    /// Defaults for tasks in a project. An entry keyed by the project's ID
+    /// wins over one matching its name (case-insensitively).
+    pub fn for_project(&self, project_id: &str, project_name: Option<&str>) -> TaskDefaultValues {
+        let entry = self.projects.get(project_id).or_else(|| {
+            let name = project_name?;
+            self.projects
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, values)| values)
+        });
+        match entry {
+            Some(values) => values.or(&self.values),
+            None => self.values.clone(),
+        }
+    }
+}
+
+/// Parse a time of day like "09:00"
+fn parse_time(time: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(time.trim(), "%H:%M")
+        .map_err(|_| anyhow!("time '{}': expected HH:MM, e.g. 09:00", time))
+}
+
+/// Priorities by name ("high") in the config file, instead of the API number
+mod priority_name {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::models::Priority;
+
+    pub fn serialize<S: Serializer>(
+        priority: &Option<Priority>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match priority {
+            Some(priority) => serializer.serialize_str(&priority.to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Priority>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|name| name.parse().map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(toml: &str) -> TaskDefaults {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn test_parse_section() {
+        let defaults = parse(
+            r#"
+timezone = "Europe/Amsterdam"
+priority = "medium"
+tags = ["cli"]
+time = "09:30"
+
+[projects.Work]
+tags = ["work"]
+priority = "high"
+"#,
+        );
+        assert_eq!(
+            defaults.values.timezone.as_deref(),
+            Some("Europe/Amsterdam")
+        );
+        assert_eq!(defaults.values.priority, Some(Priority::Medium));
+        assert_eq!(
+            defaults.values.time_of_day(),
+            NaiveTime::from_hms_opt(9, 30, 0)
+        );
+        assert_eq!(defaults.projects["Work"].priority, Some(Priority::High));
+        assert!(defaults.validate().is_ok());
+
+        // Priorities are written by name
+        let written = toml::to_string(&defaults).unwrap();
+        assert!(written.contains("priority = \"medium\""));
+        assert_eq!(parse(&written), defaults);
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        assert!(toml::from_str::<TaskDefaults>("priority = \"urgent\"").is_err());
+        assert!(parse("timezone = \"Mars/Olympus\"").validate().is_err());
+        assert!(parse("time = \"9am\"").validate().is_err());
+
+        let err = parse("[projects.Work]\ntime = \"25:00\"")
+            .validate()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid task_defaults.projects.\"Work\".time '25:00': expected HH:MM, e.g. 09:00"
+        );
+    }
+
+    #[test]
+    fn test_for_project_overrides_section() {
+        let defaults = parse(
+            r#"
+priority = "low"
+tags = ["cli"]
+all_day = true
+
+[projects.work]
+tags = ["work", "CLI"]
+priority = "high"
+
+[projects.proj2]
+all_day = false
+"#,
+        );
+
+        let work = defaults.for_project("proj1", Some("Work"));
+        assert_eq!(work.priority, Some(Priority::High));
+        assert_eq!(work.tags, vec!["cli", "work"]);
+        assert_eq!(work.all_day, Some(true));
+
+        let by_id = defaults.for_project("proj2", Some("Personal"));
+        assert_eq!(by_id.priority, Some(Priority::Low));
+        assert_eq!(by_id.all_day, Some(false));
+
+        assert_eq!(defaults.for_project("proj3", None), defaults.values);
+    }
+
+    #[test]
+    fn test_needs_project_name() {
+        assert!(!TaskDefaults::default().needs_project_name("proj1"));
+
+        let defaults = parse("[projects.proj1]\npriority = \"low\"");
+        assert!(!defaults.needs_project_name("proj1"));
+        assert!(defaults.needs_project_name("proj2"));
+    }
+}
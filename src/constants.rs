@@ -14,12 +14,36 @@ pub const OAUTH_AUTH_URL: &str = "https://ticktick.com/oauth/authorize";
 /// OAuth token exchange URL
 pub const OAUTH_TOKEN_URL: &str = "https://ticktick.com/oauth/token";
 
-/// OAuth redirect URI for local callback
+/// OAuth base URL (authorize and token endpoints live under `/oauth`)
+pub const OAUTH_BASE_URL: &str = "https://ticktick.com";
+
+/// Dida365 (China region) API base URL
+pub const DIDA365_API_BASE_URL: &str = "https://api.dida365.com/open/v1";
+
+/// Dida365 (China region) OAuth base URL
+pub const DIDA365_OAUTH_BASE_URL: &str = "https://dida365.com";
+
+/// Default OAuth redirect URI for local callback
 pub const OAUTH_REDIRECT_URI: &str = "http://localhost:8080";
 
+/// Default host of the local OAuth callback server
+pub const DEFAULT_OAUTH_CALLBACK_HOST: &str = "localhost";
+
+/// Default port of the local OAuth callback server
+pub const DEFAULT_OAUTH_CALLBACK_PORT: u16 = 8080;
+
+/// Default seconds to wait for the OAuth redirect
+pub const DEFAULT_OAUTH_CALLBACK_TIMEOUT_SECS: u64 = 300;
+
 /// OAuth scopes required by the application
 pub const OAUTH_SCOPES: &[&str] = &["tasks:write", "tasks:read"];
 
+/// OAuth scopes requested by `tickrs init --read-only`
+pub const OAUTH_READ_ONLY_SCOPES: &[&str] = &["tasks:read"];
+
+/// Scope required to create, update, complete or delete anything
+pub const SCOPE_TASKS_WRITE: &str = "tasks:write";
+
 /// Special inbox project ID
 pub const INBOX_PROJECT_ID: &str = "inbox";
 
@@ -32,12 +56,24 @@ pub const DEFAULT_PROJECT_COLOR: &str = "#FF1111";
 /// Configuration file name
 pub const CONFIG_FILE_NAME: &str = "config.toml";
 
+/// Directory-local config file, searched in the current directory and its parents
+pub const LOCAL_CONFIG_FILE_NAME: &str = ".tickrs.toml";
+
 /// Token file name
 pub const TOKEN_FILE_NAME: &str = "token";
 
+/// Encrypted token file name (`encrypted-file` token storage backend)
+pub const ENCRYPTED_TOKEN_FILE_NAME: &str = "token.enc";
+
 /// Application directory name (used in ~/.config/ and ~/.local/share/)
 pub const APP_DIR_NAME: &str = "tickrs";
 
+/// Directory of named profiles inside the application directories
+pub const PROFILES_DIR_NAME: &str = "profiles";
+
+/// File recording the profile selected with `tickrs profile use`
+pub const ACTIVE_PROFILE_FILE_NAME: &str = "active_profile";
+
 /// Environment variable for client ID
 pub const ENV_CLIENT_ID: &str = "TICKTICK_CLIENT_ID";
 
@@ -47,6 +83,111 @@ pub const ENV_CLIENT_SECRET: &str = "TICKTICK_CLIENT_SECRET";
 /// Environment variable for access token (bypasses init)
 pub const ENV_TOKEN: &str = "TICKTICK_TOKEN";
 
+/// Environment variable for the passphrase of the encrypted token file
+pub const ENV_TOKEN_PASSPHRASE: &str = "TICKRS_TOKEN_PASSPHRASE";
+
+/// Environment variable enabling read-only mode
+pub const ENV_READ_ONLY: &str = "TICKRS_READ_ONLY";
+
+/// Environment variable selecting the active profile
+pub const ENV_PROFILE: &str = "TICKRS_PROFILE";
+
+/// Editor used by `tickrs config edit`, checked before `EDITOR`
+pub const ENV_VISUAL: &str = "VISUAL";
+
+/// Editor used by `tickrs config edit`
+pub const ENV_EDITOR: &str = "EDITOR";
+
+/// Environment variable for the default project (name or ID)
+pub const ENV_DEFAULT_PROJECT: &str = "TICKRS_DEFAULT_PROJECT";
+
+/// Environment variable for the default color of new projects
+pub const ENV_DEFAULT_PROJECT_COLOR: &str = "TICKRS_DEFAULT_PROJECT_COLOR";
+
+/// Environment variable for the timezone of new tasks
+pub const ENV_TASK_TIMEZONE: &str = "TICKRS_TASK_TIMEZONE";
+
+/// Environment variable for the priority of new tasks
+pub const ENV_TASK_PRIORITY: &str = "TICKRS_TASK_PRIORITY";
+
+/// Environment variable for tags added to new tasks via `[task_defaults]` (comma-separated)
+pub const ENV_TASK_TAGS: &str = "TICKRS_TASK_TAGS";
+
+/// Environment variable for whether new tasks are all-day tasks
+pub const ENV_TASK_ALL_DAY: &str = "TICKRS_TASK_ALL_DAY";
+
+/// Environment variable for the time of day of dates given without a time ("HH:MM")
+pub const ENV_TASK_TIME: &str = "TICKRS_TASK_TIME";
+
+/// Environment variable for the service region ("ticktick" or "dida365")
+pub const ENV_REGION: &str = "TICKRS_REGION";
+
+/// Environment variable overriding the API base URL
+pub const ENV_API_BASE_URL: &str = "TICKRS_API_BASE_URL";
+
+/// Environment variable overriding the OAuth base URL
+pub const ENV_OAUTH_BASE_URL: &str = "TICKRS_OAUTH_BASE_URL";
+
+/// Environment variable for the total attempts per request
+pub const ENV_RETRY_MAX_ATTEMPTS: &str = "TICKRS_RETRY_MAX_ATTEMPTS";
+
+/// Environment variable enabling retries of non-idempotent requests
+pub const ENV_RETRY_NON_IDEMPOTENT: &str = "TICKRS_RETRY_NON_IDEMPOTENT";
+
+/// Environment variable for the HTTP connect timeout (seconds)
+pub const ENV_CONNECT_TIMEOUT: &str = "TICKRS_CONNECT_TIMEOUT";
+
+/// Environment variable for the HTTP read timeout (seconds)
+pub const ENV_READ_TIMEOUT: &str = "TICKRS_READ_TIMEOUT";
+
+/// Environment variable for the HTTPS proxy URL
+pub const ENV_HTTPS_PROXY: &str = "TICKRS_HTTPS_PROXY";
+
+/// Environment variable for extra PEM root certificate files
+pub const ENV_CA_CERTS: &str = "TICKRS_CA_CERTS";
+
+/// Environment variable for the maximum number of requests in flight
+pub const ENV_MAX_IN_FLIGHT: &str = "TICKRS_MAX_IN_FLIGHT";
+
+/// Environment variable for the request rate limit (requests per second)
+pub const ENV_REQUESTS_PER_SECOND: &str = "TICKRS_REQUESTS_PER_SECOND";
+
+/// Environment variable enabling or disabling the response cache
+pub const ENV_CACHE: &str = "TICKRS_CACHE";
+
+/// Environment variable for the response cache TTL (seconds)
+pub const ENV_CACHE_TTL: &str = "TICKRS_CACHE_TTL";
+
+/// Environment variable for the OAuth callback host
+pub const ENV_OAUTH_CALLBACK_HOST: &str = "TICKRS_OAUTH_CALLBACK_HOST";
+
+/// Environment variable for the OAuth callback port
+pub const ENV_OAUTH_CALLBACK_PORT: &str = "TICKRS_OAUTH_CALLBACK_PORT";
+
+/// Environment variable for the OAuth callback timeout (seconds)
+pub const ENV_OAUTH_CALLBACK_TIMEOUT: &str = "TICKRS_OAUTH_CALLBACK_TIMEOUT";
+
+/// Environment variable enabling or disabling PKCE for `tickrs init`
+pub const ENV_OAUTH_PKCE: &str = "TICKRS_OAUTH_PKCE";
+
+/// Environment variable for the token storage backend
+pub const ENV_TOKEN_STORAGE: &str = "TICKRS_TOKEN_STORAGE";
+
+/// Environment variable for the command printing the tokens
+pub const ENV_TOKEN_COMMAND: &str = "TICKRS_TOKEN_COMMAND";
+
+/// Environment variable for the command storing the tokens
+pub const ENV_TOKEN_STORE_COMMAND: &str = "TICKRS_TOKEN_STORE_COMMAND";
+
+/// Environment variable for the command printing the token file passphrase
+pub const ENV_PASSPHRASE_COMMAND: &str = "TICKRS_PASSPHRASE_COMMAND";
+
+/// Environment variable naming a cassette file to record API traffic to
+pub const ENV_RECORD: &str = "TICKRS_RECORD";
+
+/// Environment variable naming a cassette file to replay API responses from
+pub const ENV_REPLAY: &str = "TICKRS_REPLAY";
+
 /// Environment variable for log level
 pub const ENV_LOG_LEVEL: &str = "RUST_LOG";
 
@@ -70,10 +211,19 @@ mod tests {
         assert!(OAUTH_REDIRECT_URI.starts_with("http://localhost"));
     }
 
+    #[test]
+    fn test_oauth_urls_share_base() {
+        assert!(OAUTH_AUTH_URL.starts_with(OAUTH_BASE_URL));
+        assert!(OAUTH_TOKEN_URL.starts_with(OAUTH_BASE_URL));
+        assert!(DIDA365_API_BASE_URL.contains("dida365.com"));
+    }
+
     #[test]
     fn test_oauth_scopes() {
         assert!(OAUTH_SCOPES.contains(&"tasks:write"));
         assert!(OAUTH_SCOPES.contains(&"tasks:read"));
+        assert!(OAUTH_SCOPES.contains(&SCOPE_TASKS_WRITE));
+        assert!(!OAUTH_READ_ONLY_SCOPES.contains(&SCOPE_TASKS_WRITE));
     }
 
     #[test]
@@ -6,29 +6,39 @@ mod models;
 mod output;
 mod utils;
 
+use std::collections::BTreeMap;
 use std::env;
 use std::process::ExitCode;
 
-use clap::Parser;
+use chrono::{DateTime, Utc};
+use clap::{CommandFactory, FromArgMatches};
 
+use api::project_names::{self, MatchMode, ProjectNames};
+use api::read_only::ReadOnlyReason;
 use api::{
-    AuthHandler, CreateProjectRequest, CreateTaskRequest, TickTickClient, UpdateProjectRequest,
+    ApiError, AuthHandler, CreateProjectRequest, TickTickClient, UpdateProjectRequest,
     UpdateTaskRequest,
 };
+use cli::auth::AuthCommands;
+use cli::config::ConfigCommands;
+use cli::profile::ProfileCommands;
 use cli::project::ProjectCommands;
 use cli::subtask::SubtaskCommands;
 use cli::task::TaskCommands;
 use cli::{Cli, Commands};
-use config::{Config, TokenStorage};
-use constants::{ENV_CLIENT_ID, ENV_CLIENT_SECRET};
-use models::{ChecklistItemRequest, Priority, Status};
+use config::keys::{self, ConfigFile, Layers, ValueSource};
+use config::{profile, Config, TokenSet, TokenSource, TokenStorage};
+use constants::{ENV_CLIENT_ID, ENV_CLIENT_SECRET, OAUTH_READ_ONLY_SCOPES, OAUTH_SCOPES};
+use models::task::{TaskBuilder, API_DATE_FORMAT};
+use models::{ChecklistItemRequest, Priority, Status, Task};
 use output::json::{
-    JsonResponse, ProjectData, ProjectListData, SubtaskListData, TaskData, TaskListData,
-    VersionData,
+    error_code, error_response, AuthExportData, AuthStatusData, ConfigListData, ConfigPathData,
+    JsonResponse, LiveCheck, ProfileData, ProfileListData, ProjectData, ProjectListData,
+    SubtaskListData, TaskData, TaskListData, VersionData,
 };
 use output::text;
 use output::OutputFormat;
-use utils::date_parser::parse_date;
+use utils::date_parser::{parse_date, parse_date_at, DateParseError};
 
 /// Application name
 const APP_NAME: &str = env!("CARGO_PKG_NAME");
@@ -40,7 +50,21 @@ async fn main() -> ExitCode {
     // Load environment variables from .env file if present
     let _ = dotenvy::dotenv();
 
-    let cli = Cli::parse();
+    let args: Vec<String> = env::args_os()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    let json_requested = args.iter().any(|arg| arg == "--json");
+    let cli = match parse_cli(args) {
+        Ok(cli) => cli,
+        Err(e) => {
+            if json_requested {
+                println!("{}", error_response(&e).to_json_string());
+            } else {
+                eprintln!("{}", e);
+            }
+            return ExitCode::FAILURE;
+        }
+    };
 
     // Determine output format
     let format = if cli.json {
@@ -49,25 +73,114 @@ async fn main() -> ExitCode {
         OutputFormat::Text
     };
 
-    // Run the command and handle errors
-    let result = run_command(cli.command, format, cli.quiet).await;
+    api::cache::set_bypass(cli.no_cache);
+    if cli.timings {
+        api::timings::enable();
+    }
+    if cli.read_only {
+        api::read_only::enable();
+    }
+
+    // Report diagnostics on stderr (and to a log file) when requested
+    let result = match utils::logging::init(cli.verbose, cli.log_file.as_deref())
+        .and_then(|()| profile::set_active(cli.profile.as_deref()))
+    {
+        // Run the command and handle errors
+        Ok(()) => run_command(cli.command, format, cli.quiet).await,
+        Err(e) => Err(e),
+    };
 
-    match result {
+    let exit_code = match result {
         Ok(()) => ExitCode::SUCCESS,
         Err(e) => {
             if !cli.quiet {
-                eprintln!("{}", e);
+                match format {
+                    OutputFormat::Json => println!("{}", error_response(&e).to_json_string()),
+                    OutputFormat::Text => eprintln!("{}", e),
+                }
             }
             ExitCode::FAILURE
         }
+    };
+
+    // In JSON mode, these are part of the response's `meta` object
+    if format == OutputFormat::Text && !cli.quiet {
+        for matched in project_names::inexact_matches() {
+            eprintln!(
+                "Note: '{}' matched project '{}' ({})",
+                matched.query, matched.name, matched.id
+            );
+        }
+        if let Some(report) = api::timings::report() {
+            eprintln!("{}", report);
+        }
     }
+
+    exit_code
+}
+
+/// Parse the command line, expanding user-defined aliases and listing them in `--help`
+fn parse_cli(mut args: Vec<String>) -> anyhow::Result<Cli> {
+    let mut command = Cli::command();
+    let aliases = load_aliases(&args);
+
+    if cli::alias::invoked(&command, &args).is_some() {
+        let aliases = aliases.as_ref().map_err(|e| anyhow::anyhow!("{:#}", e))?;
+        args = cli::alias::expand(&command, args, aliases)?;
+    }
+
+    if let Some(aliases) = aliases.ok().filter(|aliases| !aliases.is_empty()) {
+        command = command.after_help(cli::alias::help(&aliases));
+    }
+    let matches = command.get_matches_from(args);
+    Ok(Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit()))
+}
+
+/// Aliases of the profile selected on the command line (or by default)
+fn load_aliases(args: &[String]) -> anyhow::Result<BTreeMap<String, String>> {
+    profile::set_active(cli::alias::profile_arg(args))?;
+    let path = Config::config_path()?;
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    Ok(Config::load_from(&path)?.alias)
 }
 
 async fn run_command(command: Commands, format: OutputFormat, quiet: bool) -> anyhow::Result<()> {
+    // Only init and auth login create a profile implicitly; don't let a typo create an empty one
+    if matches!(
+        command,
+        Commands::Auth(AuthCommands::Status | AuthCommands::Export { .. })
+            | Commands::Config(
+                ConfigCommands::Get { .. }
+                    | ConfigCommands::Set { .. }
+                    | ConfigCommands::Unset { .. }
+                    | ConfigCommands::List
+                    | ConfigCommands::Edit
+            )
+            | Commands::Project(_)
+            | Commands::Task(_)
+            | Commands::Subtask(_)
+    ) {
+        profile::require(&profile::active()?)?;
+    }
+
+    // Fail before resolving project names or making any other request
+    if command.is_mutating() && api::read_only::is_enabled() {
+        return Err(ApiError::ReadOnly(ReadOnlyReason::Requested).into());
+    }
+
     match command {
-        Commands::Init => cmd_init(format, quiet).await,
+        Commands::Init {
+            no_browser,
+            callback_host,
+            callback_port,
+        } => cmd_init(no_browser, callback_host, callback_port, format, quiet).await,
         Commands::Reset { force } => cmd_reset(force, format, quiet),
         Commands::Version => cmd_version(format, quiet),
+        Commands::Auth(cmd) => cmd_auth(cmd, format, quiet).await,
+        Commands::Config(cmd) => cmd_config(cmd, format, quiet).await,
+        Commands::Profile(cmd) => cmd_profile(cmd, format, quiet),
         Commands::Project(cmd) => cmd_project(cmd, format, quiet).await,
         Commands::Task(cmd) => cmd_task(cmd, format, quiet).await,
         Commands::Subtask(cmd) => cmd_subtask(cmd, format, quiet).await,
@@ -75,7 +188,13 @@ async fn run_command(command: Commands, format: OutputFormat, quiet: bool) -> an
 }
 
 /// Initialize OAuth authentication
-async fn cmd_init(format: OutputFormat, quiet: bool) -> anyhow::Result<()> {
+async fn cmd_init(
+    no_browser: bool,
+    callback_host: Option<String>,
+    callback_port: Option<u16>,
+    format: OutputFormat,
+    quiet: bool,
+) -> anyhow::Result<()> {
     // Check if already initialized
     if TokenStorage::exists()? {
         let message =
@@ -102,30 +221,81 @@ async fn cmd_init(format: OutputFormat, quiet: bool) -> anyhow::Result<()> {
     })?;
 
     // Create auth handler and get URL first
-    let auth = AuthHandler::new(client_id, client_secret);
-    let (auth_url, _) = auth.get_auth_url()?;
-
-    if !quiet && format == OutputFormat::Text {
-        println!("Opening browser for TickTick authorization...");
-        println!();
-        println!("If the browser doesn't open, visit this URL:");
-        println!("{}", auth_url);
-        println!();
+    let config = Config::load()?;
+    let endpoints = config.endpoints()?;
+    let http_config = config.http;
+    let mut callback_config = config.oauth;
+    if let Some(host) = callback_host {
+        callback_config.callback_host = host;
+    }
+    if let Some(port) = callback_port {
+        callback_config.callback_port = port;
     }
+    callback_config.validate()?;
+    let read_only = api::read_only::is_enabled();
+    let auth = AuthHandler::new(client_id, client_secret)
+        .with_endpoints(endpoints)
+        .with_http_config(http_config)
+        .with_callback_config(callback_config)
+        .with_scopes(if read_only {
+            OAUTH_READ_ONLY_SCOPES
+        } else {
+            OAUTH_SCOPES
+        });
+    let (auth_url, csrf_token) = auth.get_auth_url()?;
 
     // Run OAuth flow
-    let token = auth.run_oauth_flow().await?;
+    let tokens = if no_browser {
+        // The URL has to be shown even in quiet or JSON mode; keep stdout clean there
+        let instructions = format!(
+            "Visit this URL to authorize tickrs:\n\n{}\n\n\
+             After authorizing, your browser is redirected to a page that may fail to load.\n\
+             Paste the full URL from its address bar (or just the code) here:",
+            auth_url
+        );
+        if !quiet && format == OutputFormat::Text {
+            println!("{}", instructions);
+            print!("> ");
+            std::io::Write::flush(&mut std::io::stdout())?;
+        } else {
+            eprintln!("{}", instructions);
+        }
 
-    // Save token
-    TokenStorage::save(&token)?;
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        auth.complete_with_pasted_code(&input, &csrf_token).await?
+    } else {
+        if !quiet && format == OutputFormat::Text {
+            println!("Opening browser for TickTick authorization...");
+            println!();
+            println!("If the browser doesn't open, visit this URL:");
+            println!("{}", auth_url);
+            println!();
+        }
 
-    // Initialize config
-    let config = Config::default();
-    config.save()?;
+        // Don't fail if the browser can't be opened (e.g., headless environments)
+        let _ = webbrowser::open(&auth_url);
+        auth.complete_with_callback(&csrf_token).await?
+    };
+
+    // Save tokens, including the refresh token and expiry
+    TokenStorage::save_set(&tokens)?;
 
-    let message = "Authentication successful";
+    let active = profile::active()?;
+    let mut details = Vec::new();
+    if active != profile::DEFAULT_PROFILE {
+        details.push(format!("profile '{}'", active));
+    }
+    if api::read_only::check(&tokens.scopes).is_some() {
+        details.push("read-only".to_string());
+    }
+    let message = if details.is_empty() {
+        "Authentication successful".to_string()
+    } else {
+        format!("Authentication successful ({})", details.join(", "))
+    };
     if !quiet {
-        output_message(format, message, "SUCCESS")?;
+        output_message(format, &message, "SUCCESS")?;
     }
 
     Ok(())
@@ -148,6 +318,10 @@ fn cmd_reset(force: bool, format: OutputFormat, quiet: bool) -> anyhow::Result<(
 
     // Confirm unless --force is specified
     if !force && format == OutputFormat::Text {
+        let active = profile::active()?;
+        if active != profile::DEFAULT_PROFILE {
+            println!("Profile: {}", active);
+        }
         println!("This will delete your stored credentials and configuration.");
         println!("You will need to re-authenticate with 'tickrs init'.");
         print!("Continue? [y/N] ");
@@ -201,6 +375,526 @@ fn cmd_version(format: OutputFormat, quiet: bool) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Handle auth commands
+async fn cmd_auth(cmd: AuthCommands, format: OutputFormat, quiet: bool) -> anyhow::Result<()> {
+    match cmd {
+        AuthCommands::Status => cmd_auth_status(format, quiet).await,
+        AuthCommands::Login { token_stdin: _ } => cmd_auth_login(format, quiet),
+        AuthCommands::Export {
+            include_client_secret,
+        } => cmd_auth_export(include_client_secret, format, quiet),
+    }
+}
+
+/// Store tokens read from stdin
+fn cmd_auth_login(format: OutputFormat, quiet: bool) -> anyhow::Result<()> {
+    let mut input = String::new();
+    std::io::Read::read_to_string(&mut std::io::stdin(), &mut input)?;
+    let mut tokens = TokenSet::parse_import(&input)?;
+
+    // Refreshing requires the client the tokens were issued to; its secret
+    // is read from the environment when refreshing
+    if tokens.refresh_token.is_some() && tokens.client_id.is_none() {
+        tokens.client_id = env::var(ENV_CLIENT_ID).ok();
+    }
+
+    // Creates the profile if needed, like init
+    Config::load()?;
+    TokenStorage::save_set(&tokens)?;
+
+    if quiet {
+        return Ok(());
+    }
+
+    let active = profile::active()?;
+    let mut message = if active == profile::DEFAULT_PROFILE {
+        "Tokens stored".to_string()
+    } else {
+        format!("Tokens stored (profile '{}')", active)
+    };
+    if tokens.refresh_token.is_none() {
+        message.push_str(". Without a refresh token, run this again once the access token expires");
+    } else if tokens.client_id.is_none()
+        || (tokens.client_secret.is_none() && env::var(ENV_CLIENT_SECRET).is_err())
+    {
+        message.push_str(&format!(
+            ". Set {} and {} to refresh the access token",
+            ENV_CLIENT_ID, ENV_CLIENT_SECRET
+        ));
+    }
+    output_message(format, &message, "SUCCESS")
+}
+
+/// Print the stored tokens, with the client secret only if asked for
+fn cmd_auth_export(
+    include_client_secret: bool,
+    format: OutputFormat,
+    quiet: bool,
+) -> anyhow::Result<()> {
+    let mut tokens = TokenStorage::load_stored()?.ok_or(ApiError::NotAuthenticated)?;
+    if include_client_secret {
+        if tokens.client_secret.is_none() {
+            tokens.client_secret = env::var(ENV_CLIENT_SECRET).ok();
+        }
+        if tokens.client_secret.is_none() {
+            anyhow::bail!(
+                "No client secret to include. Set {} to the secret the tokens were issued with",
+                ENV_CLIENT_SECRET
+            );
+        }
+    } else {
+        tokens.client_secret = None;
+    }
+
+    if quiet {
+        return Ok(());
+    }
+
+    if format == OutputFormat::Text && std::io::IsTerminal::is_terminal(&std::io::stdout()) {
+        eprintln!("Warning: the output contains credentials; treat it like a password");
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let response = JsonResponse::success(AuthExportData { tokens });
+            println!("{}", response.to_json_string());
+        }
+        OutputFormat::Text => {
+            println!("{}", serde_json::to_string_pretty(&tokens)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Report where the token comes from and whether TickTick accepts it
+async fn cmd_auth_status(format: OutputFormat, quiet: bool) -> anyhow::Result<()> {
+    let mut token = TokenStorage::status()?;
+
+    // Only contact TickTick if there is a token to check
+    let live_check = if token.source != TokenSource::None {
+        // A cached project list would prove nothing
+        api::cache::set_bypass(true);
+        let result = match TickTickClient::new() {
+            Ok(client) => client.list_projects().await.map_err(anyhow::Error::from),
+            Err(e) => Err(e),
+        };
+        Some(match result {
+            Ok(_) => LiveCheck {
+                ok: true,
+                error_code: None,
+                error: None,
+            },
+            Err(e) => LiveCheck {
+                ok: false,
+                error_code: Some(error_code(&e).to_string()),
+                error: Some(e.to_string()),
+            },
+        })
+    } else {
+        None
+    };
+
+    // The check refreshes an expired access token
+    if token.expired && live_check.as_ref().is_some_and(|check| check.ok) {
+        token = TokenStorage::status()?;
+    }
+
+    let authenticated = live_check.as_ref().is_some_and(|check| check.ok);
+    let needs_reauth = (token.source == TokenSource::None && token.error.is_none())
+        || live_check.as_ref().is_some_and(|check| {
+            matches!(
+                check.error_code.as_deref(),
+                Some("AUTH_REQUIRED" | "AUTH_EXPIRED")
+            )
+        });
+    let data = AuthStatusData {
+        profile: profile::active()?,
+        authenticated,
+        needs_reauth,
+        read_only: api::read_only::check(&token.scopes).is_some(),
+        token,
+        live_check,
+    };
+
+    if quiet {
+        return Ok(());
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let response = JsonResponse::success(data);
+            println!("{}", response.to_json_string());
+        }
+        OutputFormat::Text => {
+            println!("{}", text::format_auth_status(&data));
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle config commands
+async fn cmd_config(cmd: ConfigCommands, format: OutputFormat, quiet: bool) -> anyhow::Result<()> {
+    match cmd {
+        ConfigCommands::Get { key } => cmd_config_get(&key, format, quiet),
+        ConfigCommands::Set { key, value } => cmd_config_set(&key, &value, format, quiet).await,
+        ConfigCommands::Unset { key } => cmd_config_unset(&key, format, quiet),
+        ConfigCommands::List => cmd_config_list(format, quiet),
+        ConfigCommands::Path => cmd_config_path(format, quiet),
+        ConfigCommands::Edit => cmd_config_edit(format, quiet),
+    }
+}
+
+/// Show the value of a setting
+fn cmd_config_get(key: &str, format: OutputFormat, quiet: bool) -> anyhow::Result<()> {
+    let key = keys::lookup(key)?;
+    let entry = ConfigFile::open()?.entry(key, &Layers::current()?)?;
+
+    if quiet {
+        return Ok(());
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let response = JsonResponse::success(entry);
+            println!("{}", response.to_json_string());
+        }
+        OutputFormat::Text => {
+            println!("{}", text::format_config_value(&entry.value));
+        }
+    }
+
+    Ok(())
+}
+
+/// Change a setting in the config file
+async fn cmd_config_set(
+    key: &str,
+    value: &str,
+    format: OutputFormat,
+    quiet: bool,
+) -> anyhow::Result<()> {
+    let key = keys::lookup(key)?;
+    let parsed = key.parse_value(value)?;
+
+    if key.name == "default_project_id" {
+        let id = value.trim();
+        let client = TickTickClient::new()?;
+        let projects = client.list_projects().await?;
+        if !projects.iter().any(|p| p.id == id) {
+            anyhow::bail!(
+                "Unknown project ID '{}'. Run 'tickrs project list' to see project IDs",
+                id
+            );
+        }
+    }
+
+    let mut file = ConfigFile::open()?;
+    file.set(key, parsed)?;
+    file.save()?;
+    let layers = Layers::current()?;
+    let entry = file.entry(key, &layers)?;
+
+    if quiet {
+        return Ok(());
+    }
+
+    let mut message = format!("{} set to {}", key.name, value.trim());
+    match (entry.source, &layers.local) {
+        (ValueSource::Env, _) => {
+            message.push_str(&format!(" (overridden by {})", key.env));
+        }
+        (ValueSource::Local, Some(local)) => {
+            message.push_str(&format!(" (overridden by {})", local.path.display()));
+        }
+        _ => {}
+    }
+    match format {
+        OutputFormat::Json => {
+            let response = JsonResponse::success_with_message(entry, &message);
+            println!("{}", response.to_json_string());
+        }
+        OutputFormat::Text => {
+            println!("{}", text::format_success(&message));
+        }
+    }
+
+    Ok(())
+}
+
+/// Remove a setting from the config file
+fn cmd_config_unset(key: &str, format: OutputFormat, quiet: bool) -> anyhow::Result<()> {
+    let key = keys::lookup(key)?;
+    let mut file = ConfigFile::open()?;
+    let message = if file.unset(key) {
+        file.save()?;
+        format!("{} removed from the config file", key.name)
+    } else {
+        format!("{} is not set in the config file", key.name)
+    };
+    let entry = file.entry(key, &Layers::current()?)?;
+
+    if quiet {
+        return Ok(());
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let response = JsonResponse::success_with_message(entry, &message);
+            println!("{}", response.to_json_string());
+        }
+        OutputFormat::Text => {
+            println!("{}", text::format_success(&message));
+        }
+    }
+
+    Ok(())
+}
+
+/// List all settings
+fn cmd_config_list(format: OutputFormat, quiet: bool) -> anyhow::Result<()> {
+    let file = ConfigFile::open()?;
+    let layers = Layers::current()?;
+    let data = ConfigListData {
+        profile: profile::active()?,
+        path: file.path().display().to_string(),
+        local_path: layers
+            .local
+            .as_ref()
+            .map(|local| local.path.display().to_string()),
+        settings: file.entries(&layers)?,
+    };
+
+    if quiet {
+        return Ok(());
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let response = JsonResponse::success(data);
+            println!("{}", response.to_json_string());
+        }
+        OutputFormat::Text => {
+            println!("{}", text::format_config_list(&data));
+        }
+    }
+
+    Ok(())
+}
+
+/// Print the path of the config file
+fn cmd_config_path(format: OutputFormat, quiet: bool) -> anyhow::Result<()> {
+    let path = Config::config_path()?;
+
+    if quiet {
+        return Ok(());
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let data = ConfigPathData {
+                path: path.display().to_string(),
+                exists: path.exists(),
+                local_path: config::local::discover()?
+                    .map(|local| local.path.display().to_string()),
+            };
+            let response = JsonResponse::success(data);
+            println!("{}", response.to_json_string());
+        }
+        OutputFormat::Text => {
+            println!("{}", path.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Open the config file in an editor and check it afterwards
+fn cmd_config_edit(format: OutputFormat, quiet: bool) -> anyhow::Result<()> {
+    let path = Config::config_path()?;
+    if !path.exists() {
+        Config::default().save_to(&path)?;
+    }
+
+    let editor = [constants::ENV_VISUAL, constants::ENV_EDITOR]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| {
+            if cfg!(windows) {
+                "notepad".to_string()
+            } else {
+                "vi".to_string()
+            }
+        });
+
+    // Allow editors with arguments, e.g. "code --wait"
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or_default();
+    let status = std::process::Command::new(program)
+        .args(words)
+        .arg(&path)
+        .status()
+        .map_err(|e| anyhow::anyhow!("Failed to run editor '{}': {}", editor, e))?;
+    if !status.success() {
+        anyhow::bail!("Editor '{}' exited with {}", editor, status);
+    }
+
+    if let Err(e) = ConfigFile::open_at(&path).and_then(|file| file.config()?.validate()) {
+        anyhow::bail!(
+            "{:#}. Run 'tickrs config edit' again to fix {}",
+            e,
+            path.display()
+        );
+    }
+
+    if !quiet {
+        output_message(format, "Config file saved", "SUCCESS")?;
+    }
+
+    Ok(())
+}
+
+/// Handle profile commands
+fn cmd_profile(cmd: ProfileCommands, format: OutputFormat, quiet: bool) -> anyhow::Result<()> {
+    match cmd {
+        ProfileCommands::List => cmd_profile_list(format, quiet),
+        ProfileCommands::Add { name } => cmd_profile_add(&name, format, quiet),
+        ProfileCommands::Remove { name, force } => cmd_profile_remove(&name, force, format, quiet),
+        ProfileCommands::Use { name } => cmd_profile_use(&name, format, quiet),
+    }
+}
+
+/// List all profiles
+fn cmd_profile_list(format: OutputFormat, quiet: bool) -> anyhow::Result<()> {
+    let profiles = profile::list()?
+        .iter()
+        .map(|name| profile::info(name))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    if quiet {
+        return Ok(());
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let data = ProfileListData {
+                profiles,
+                active: profile::active()?,
+            };
+            let response = JsonResponse::success(data);
+            println!("{}", response.to_json_string());
+        }
+        OutputFormat::Text => {
+            println!("{}", text::format_profile_list(&profiles));
+        }
+    }
+
+    Ok(())
+}
+
+/// Create a profile
+fn cmd_profile_add(name: &str, format: OutputFormat, quiet: bool) -> anyhow::Result<()> {
+    profile::create(name)?;
+
+    if quiet {
+        return Ok(());
+    }
+
+    let message = format!(
+        "Profile '{}' created. Run 'tickrs --profile {} init' to authenticate.",
+        name, name
+    );
+    match format {
+        OutputFormat::Json => {
+            let data = ProfileData {
+                profile: profile::info(name)?,
+            };
+            let response = JsonResponse::success_with_message(data, &message);
+            println!("{}", response.to_json_string());
+        }
+        OutputFormat::Text => {
+            println!("{}", text::format_success(&message));
+        }
+    }
+
+    Ok(())
+}
+
+/// Delete a profile
+fn cmd_profile_remove(
+    name: &str,
+    force: bool,
+    format: OutputFormat,
+    quiet: bool,
+) -> anyhow::Result<()> {
+    profile::validate_name(name)?;
+    if name != profile::DEFAULT_PROFILE {
+        profile::require(name)?;
+    }
+
+    // Confirm unless --force is specified
+    if !force && format == OutputFormat::Text && name != profile::DEFAULT_PROFILE {
+        print!(
+            "Delete profile '{}' with its configuration and credentials? [y/N] ",
+            name
+        );
+        std::io::Write::flush(&mut std::io::stdout())?;
+
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("Aborted.");
+            return Ok(());
+        }
+    }
+
+    profile::remove(name)?;
+
+    let message = format!("Profile '{}' removed", name);
+    if !quiet {
+        output_message(format, &message, "SUCCESS")?;
+    }
+
+    Ok(())
+}
+
+/// Select the profile used by default
+fn cmd_profile_use(name: &str, format: OutputFormat, quiet: bool) -> anyhow::Result<()> {
+    profile::validate_name(name)?;
+    profile::select(name)?;
+
+    if quiet {
+        return Ok(());
+    }
+
+    let mut message = format!("Active profile set to '{}'", name);
+    if let Ok(env_profile) = env::var(constants::ENV_PROFILE) {
+        if !env_profile.trim().is_empty() && env_profile.trim() != name {
+            message.push_str(&format!(
+                " ({} is set and still takes precedence)",
+                constants::ENV_PROFILE
+            ));
+        }
+    }
+    match format {
+        OutputFormat::Json => {
+            let data = ProfileData {
+                profile: profile::info(name)?,
+            };
+            let response = JsonResponse::success_with_message(data, &message);
+            println!("{}", response.to_json_string());
+        }
+        OutputFormat::Text => {
+            println!("{}", text::format_success(&message));
+        }
+    }
+
+    Ok(())
+}
+
 /// Output a message in the appropriate format
 fn output_message(format: OutputFormat, message: &str, code: &str) -> anyhow::Result<()> {
     match format {
@@ -299,19 +993,27 @@ async fn cmd_project_use(
     format: OutputFormat,
     quiet: bool,
 ) -> anyhow::Result<()> {
-    let client = TickTickClient::new()?;
+    let config = Config::load()?;
+    let client = TickTickClient::from_config(&config)?;
     let projects = client.list_projects().await?;
 
-    // Find project by name or ID
+    // Find project by alias, ID, name or prefix; the ID ends up in the config
+    let name_or_id = project_names::expand_alias(name_or_id, &config.project_alias);
+    let id = ProjectNames::from_projects(&projects)
+        .resolve_recorded(name_or_id, MatchMode::Strict)?
+        .id;
     let project = projects
         .iter()
-        .find(|p| p.id == name_or_id || p.name.eq_ignore_ascii_case(name_or_id))
+        .find(|p| p.id == id)
         .ok_or_else(|| anyhow::anyhow!("Project not found: {}", name_or_id))?;
 
-    // Update config with the project ID
-    let mut config = Config::load()?;
-    config.default_project_id = Some(project.id.clone());
-    config.save()?;
+    // Update config with the project ID, keeping the rest of the file as is
+    let mut file = ConfigFile::open()?;
+    file.set(
+        keys::lookup("default_project_id")?,
+        project.id.as_str().into(),
+    )?;
+    file.save()?;
 
     if quiet {
         return Ok(());
@@ -464,6 +1166,7 @@ async fn cmd_task(cmd: TaskCommands, format: OutputFormat, quiet: bool) -> anyho
         TaskCommands::List {
             project_id,
             project_name,
+            all,
             priority,
             tag,
             status,
@@ -471,6 +1174,7 @@ async fn cmd_task(cmd: TaskCommands, format: OutputFormat, quiet: bool) -> anyho
             cmd_task_list(
                 project_id,
                 project_name,
+                all,
                 priority,
                 tag,
                 status,
@@ -557,10 +1261,10 @@ async fn cmd_task(cmd: TaskCommands, format: OutputFormat, quiet: bool) -> anyho
             force,
         } => cmd_task_delete(&id, project_id, project_name, force, format, quiet).await,
         TaskCommands::Complete {
-            id,
+            ids,
             project_id,
             project_name,
-        } => cmd_task_complete(&id, project_id, project_name, format, quiet).await,
+        } => cmd_task_complete(&ids, project_id, project_name, format, quiet).await,
         TaskCommands::Uncomplete {
             id,
             project_id,
@@ -569,52 +1273,136 @@ async fn cmd_task(cmd: TaskCommands, format: OutputFormat, quiet: bool) -> anyho
     }
 }
 
-/// Resolve project name to ID by looking up all projects
-async fn resolve_project_name(name: &str) -> anyhow::Result<String> {
-    let client = TickTickClient::new()?;
+/// Resolve a project alias, ID or name to an ID. Exact matches are served
+/// from the cached name map; anything else looks at a fresh project list,
+/// and prefix or fuzzy matches are reported once the command has finished.
+/// Commands that modify data pass [`MatchMode::Strict`] so a fuzzy match
+/// fails instead of picking a project.
+async fn resolve_project_name(
+    config: &Config,
+    client: &TickTickClient,
+    name: &str,
+    mode: MatchMode,
+) -> anyhow::Result<String> {
+    let name = project_names::expand_alias(name, &config.project_alias);
+    if let Some(project) = client
+        .cached_project_names()
+        .and_then(|names| names.find_exact(name))
+    {
+        return Ok(project.id);
+    }
+
     let projects = client.list_projects().await?;
-    let project = projects
-        .iter()
-        .find(|p| p.name.eq_ignore_ascii_case(name))
-        .ok_or_else(|| anyhow::anyhow!("Project not found: {}", name))?;
-    Ok(project.id.clone())
+    Ok(ProjectNames::from_projects(&projects)
+        .resolve_recorded(name, mode)?
+        .id)
 }
 
-/// Get the project ID from argument, name lookup, or config default
-async fn get_project_id(
+/// The project a command was pointed at, before any name is looked up
+enum ProjectArg {
+    Id(String),
+    Name(String),
+    /// A name from `TICKRS_DEFAULT_PROJECT`
+    DefaultName(String),
+}
+
+/// Pick the project from argument or config default. This runs before the
+/// client is built, so flag mistakes are reported even when logged out.
+fn project_arg(
+    config: &Config,
     project_id: Option<String>,
     project_name: Option<String>,
-) -> anyhow::Result<String> {
+) -> anyhow::Result<ProjectArg> {
     match (project_id, project_name) {
         (Some(_), Some(_)) => {
             anyhow::bail!("Cannot specify both --project-id and --project-name")
         }
-        (Some(id), None) => Ok(id),
-        (None, Some(name)) => resolve_project_name(&name).await,
+        (Some(id), None) => Ok(ProjectArg::Id(id)),
+        (None, Some(name)) => Ok(ProjectArg::Name(name)),
         (None, None) => {
-            let config = Config::load()?;
-            config.default_project_id.ok_or_else(|| {
+            let default = config.default_project_id.clone().ok_or_else(|| {
                 anyhow::anyhow!(
                     "No project specified. Use --project-id, --project-name, or set a default with 'tickrs project use <name>'"
                 )
-            })
+            })?;
+
+            // TICKRS_DEFAULT_PROJECT may name the project instead of giving its ID
+            if env::var(constants::ENV_DEFAULT_PROJECT).is_ok_and(|v| !v.trim().is_empty()) {
+                return Ok(ProjectArg::DefaultName(default));
+            }
+            Ok(ProjectArg::Id(default))
         }
     }
 }
 
-/// List tasks in a project
+/// Get the project ID from argument, name lookup, or config default
+async fn get_project_id(
+    config: &Config,
+    client: &TickTickClient,
+    project: ProjectArg,
+    mode: MatchMode,
+) -> anyhow::Result<String> {
+    match project {
+        ProjectArg::Id(id) => Ok(id),
+        ProjectArg::Name(name) => resolve_project_name(config, client, &name, mode).await,
+        ProjectArg::DefaultName(name) => resolve_project_name(config, client, &name, mode)
+            .await
+            .map_err(|e| {
+                let message = format!("{} (from {})", e, constants::ENV_DEFAULT_PROJECT);
+                e.context(message)
+            }),
+    }
+}
+
+/// Task defaults for new tasks in a project, looking up the project's name
+/// only if `[task_defaults.projects]` has entries not keyed by its ID
+async fn task_defaults(
+    config: &Config,
+    client: &TickTickClient,
+    project_id: &str,
+) -> anyhow::Result<config::TaskDefaultValues> {
+    let project_name = if config.task_defaults.needs_project_name(project_id) {
+        let names = match client.cached_project_names() {
+            Some(names) if names.projects.iter().any(|p| p.id == project_id) => names,
+            _ => ProjectNames::from_projects(&client.list_projects().await?),
+        };
+        names
+            .projects
+            .into_iter()
+            .find(|p| p.id == project_id)
+            .map(|p| p.name)
+    } else {
+        None
+    };
+    Ok(config
+        .task_defaults
+        .for_project(project_id, project_name.as_deref()))
+}
+
+/// List tasks in a project, or across all projects
+#[allow(clippy::too_many_arguments)]
 async fn cmd_task_list(
     project_id: Option<String>,
     project_name: Option<String>,
+    all: bool,
     priority_filter: Option<Priority>,
     tag_filter: Option<String>,
     status_filter: Option<String>,
     format: OutputFormat,
     quiet: bool,
 ) -> anyhow::Result<()> {
-    let project_id = get_project_id(project_id, project_name).await?;
-    let client = TickTickClient::new()?;
-    let mut tasks = client.list_tasks(&project_id).await?;
+    let config = Config::load()?;
+    let project = (!all)
+        .then(|| project_arg(&config, project_id, project_name))
+        .transpose()?;
+    let client = TickTickClient::from_config(&config)?;
+    let mut tasks = match project {
+        None => list_tasks_in_all_projects(&client).await?,
+        Some(project) => {
+            let project_id = get_project_id(&config, &client, project, MatchMode::Fuzzy).await?;
+            client.list_tasks(&project_id).await?
+        }
+    };
 
     // Apply filters
     if let Some(priority) = priority_filter {
@@ -663,6 +1451,23 @@ async fn cmd_task_list(
     Ok(())
 }
 
+/// List tasks of every project concurrently, in project order
+async fn list_tasks_in_all_projects(client: &TickTickClient) -> anyhow::Result<Vec<Task>> {
+    let projects = client.list_projects().await?;
+
+    let results = client
+        .batch(&projects, |project| client.list_tasks(&project.id))
+        .await;
+
+    let mut tasks = Vec::new();
+    for (project, result) in projects.iter().zip(results) {
+        let project_tasks = result
+            .map_err(|e| anyhow::anyhow!("Failed to list tasks in '{}': {}", project.name, e))?;
+        tasks.extend(project_tasks);
+    }
+    Ok(tasks)
+}
+
 /// Show task details
 async fn cmd_task_show(
     task_id: &str,
@@ -671,8 +1476,10 @@ async fn cmd_task_show(
     format: OutputFormat,
     quiet: bool,
 ) -> anyhow::Result<()> {
-    let project_id = get_project_id(project_id, project_name).await?;
-    let client = TickTickClient::new()?;
+    let config = Config::load()?;
+    let project = project_arg(&config, project_id, project_name)?;
+    let client = TickTickClient::from_config(&config)?;
+    let project_id = get_project_id(&config, &client, project, MatchMode::Fuzzy).await?;
     let task = client.get_task(&project_id, task_id).await?;
 
     if quiet {
@@ -705,43 +1512,61 @@ async fn cmd_task_create(
     date: Option<String>,
     start: Option<String>,
     due: Option<String>,
-    all_day: bool,
+    all_day: Option<bool>,
     timezone: Option<String>,
     items: Option<String>,
     format: OutputFormat,
     quiet: bool,
 ) -> anyhow::Result<()> {
-    let project_id = get_project_id(project_id, project_name).await?;
-
-    // Parse dates
-    let (start_date, due_date) = parse_task_dates(date, start, due)?;
-
-    // Parse tags
-    let tags_vec = tags.map(|t| t.split(',').map(|s| s.trim().to_string()).collect());
-
-    // Parse subtasks/items
-    let items_vec = items.map(|i| {
-        i.split(',')
-            .enumerate()
-            .map(|(idx, s)| ChecklistItemRequest::new(s.trim()).with_sort_order(idx as i64))
-            .collect()
-    });
+    let config = Config::load()?;
+    let project = project_arg(&config, project_id, project_name)?;
+    let client = TickTickClient::from_config(&config)?;
+    let project_id = get_project_id(&config, &client, project, MatchMode::Strict).await?;
+    let defaults = task_defaults(&config, &client, &project_id).await?;
+
+    // Parse dates, at the default time of day for dates given without a time
+    let time_zone = timezone.or_else(|| defaults.timezone.clone());
+    let time_of_day = defaults
+        .time_of_day()
+        .filter(|_| !all_day.or(defaults.all_day).unwrap_or(false));
+    let (start_date, due_date) = parse_date_flags(date, start, due, |input| match time_of_day {
+        Some(time) => parse_date_at(input, time, time_zone.as_deref()),
+        None => parse_date(input),
+    })?;
 
-    let request = CreateTaskRequest {
-        title: title.to_string(),
-        project_id: project_id.clone(),
-        content,
-        is_all_day: if all_day { Some(true) } else { None },
-        start_date,
-        due_date,
-        priority: priority.map(|p| p.to_api_value()),
-        time_zone: timezone,
-        tags: tags_vec,
-        items: items_vec,
-    };
+    let mut builder = TaskBuilder::new(&project_id, title).defaults(defaults);
+    if let Some(content) = content {
+        builder = builder.content(content);
+    }
+    if let Some(priority) = priority {
+        builder = builder.priority(priority);
+    }
+    if let Some(tags) = tags {
+        builder = builder.tags(tags.split(',').map(|s| s.trim().to_string()).collect());
+    }
+    if let Some(start_date) = start_date {
+        builder = builder.start_date(start_date);
+    }
+    if let Some(due_date) = due_date {
+        builder = builder.due_date(due_date);
+    }
+    if let Some(all_day) = all_day {
+        builder = builder.all_day(all_day);
+    }
+    if let Some(time_zone) = time_zone {
+        builder = builder.time_zone(time_zone);
+    }
+    if let Some(items) = items {
+        builder = builder.items(
+            items
+                .split(',')
+                .enumerate()
+                .map(|(idx, s)| ChecklistItemRequest::new(s.trim()).with_sort_order(idx as i64))
+                .collect(),
+        );
+    }
 
-    let client = TickTickClient::new()?;
-    let task = client.create_task(&request).await?;
+    let task = client.create_task(&builder.into_create_request()).await?;
 
     if quiet {
         return Ok(());
@@ -780,7 +1605,10 @@ async fn cmd_task_update(
     format: OutputFormat,
     quiet: bool,
 ) -> anyhow::Result<()> {
-    let project_id = get_project_id(project_id, project_name).await?;
+    let config = Config::load()?;
+    let project = project_arg(&config, project_id, project_name)?;
+    let client = TickTickClient::from_config(&config)?;
+    let project_id = get_project_id(&config, &client, project, MatchMode::Strict).await?;
 
     // Parse dates
     let (start_date, due_date) = parse_task_dates(date, start, due)?;
@@ -811,7 +1639,6 @@ async fn cmd_task_update(
         items: items_vec,
     };
 
-    let client = TickTickClient::new()?;
     let task = client.update_task(task_id, &request).await?;
 
     if quiet {
@@ -841,7 +1668,10 @@ async fn cmd_task_delete(
     format: OutputFormat,
     quiet: bool,
 ) -> anyhow::Result<()> {
-    let project_id = get_project_id(project_id, project_name).await?;
+    let config = Config::load()?;
+    let project = project_arg(&config, project_id, project_name)?;
+    let client = TickTickClient::from_config(&config)?;
+    let project_id = get_project_id(&config, &client, project, MatchMode::Strict).await?;
 
     // Confirm unless --force is specified
     if !force && format == OutputFormat::Text {
@@ -857,7 +1687,6 @@ async fn cmd_task_delete(
         }
     }
 
-    let client = TickTickClient::new()?;
     client.delete_task(&project_id, task_id).await?;
 
     if quiet {
@@ -878,31 +1707,63 @@ async fn cmd_task_delete(
     Ok(())
 }
 
-/// Mark a task as complete
+/// Mark one or more tasks as complete
 async fn cmd_task_complete(
-    task_id: &str,
+    task_ids: &[String],
     project_id: Option<String>,
     project_name: Option<String>,
     format: OutputFormat,
     quiet: bool,
 ) -> anyhow::Result<()> {
-    let project_id = get_project_id(project_id, project_name).await?;
-
-    let client = TickTickClient::new()?;
-    client.complete_task(&project_id, task_id).await?;
+    let config = Config::load()?;
+    let project = project_arg(&config, project_id, project_name)?;
+    let client = TickTickClient::from_config(&config)?;
+    let project_id = get_project_id(&config, &client, project, MatchMode::Strict).await?;
+    if let [task_id] = task_ids {
+        client.complete_task(&project_id, task_id).await?;
+    } else {
+        let results = client
+            .batch(task_ids, |task_id| {
+                client.complete_task(&project_id, task_id)
+            })
+            .await;
+        let failures: Vec<String> = task_ids
+            .iter()
+            .zip(results)
+            .filter_map(|(id, result)| result.err().map(|e| format!("{}: {}", id, e)))
+            .collect();
+        if !failures.is_empty() {
+            anyhow::bail!(
+                "Failed to complete {} of {} tasks:\n{}",
+                failures.len(),
+                task_ids.len(),
+                failures.join("\n")
+            );
+        }
+    }
 
     if quiet {
         return Ok(());
     }
 
-    let message = "Task marked as complete";
+    let message = if task_ids.len() == 1 {
+        "Task marked as complete".to_string()
+    } else {
+        format!("{} tasks marked as complete", task_ids.len())
+    };
     match format {
         OutputFormat::Json => {
-            let response = JsonResponse::success_with_message(serde_json::json!({}), message);
+            // A single task keeps the original empty data object
+            let data = if task_ids.len() == 1 {
+                serde_json::json!({})
+            } else {
+                serde_json::json!({ "task_ids": task_ids })
+            };
+            let response = JsonResponse::success_with_message(data, &message);
             println!("{}", response.to_json_string());
         }
         OutputFormat::Text => {
-            println!("{}", text::format_success(message));
+            println!("{}", text::format_success(&message));
         }
     }
 
@@ -917,9 +1778,10 @@ async fn cmd_task_uncomplete(
     format: OutputFormat,
     quiet: bool,
 ) -> anyhow::Result<()> {
-    let project_id = get_project_id(project_id, project_name).await?;
-
-    let client = TickTickClient::new()?;
+    let config = Config::load()?;
+    let project = project_arg(&config, project_id, project_name)?;
+    let client = TickTickClient::from_config(&config)?;
+    let project_id = get_project_id(&config, &client, project, MatchMode::Strict).await?;
     let task = client.uncomplete_task(&project_id, task_id).await?;
 
     if quiet {
@@ -945,19 +1807,28 @@ fn parse_task_dates(
     start: Option<String>,
     due: Option<String>,
 ) -> anyhow::Result<(Option<String>, Option<String>)> {
+    let (start_date, due_date) = parse_date_flags(date, start, due, parse_date)?;
+    let format = |dt: DateTime<Utc>| dt.format(API_DATE_FORMAT).to_string();
+    Ok((start_date.map(format), due_date.map(format)))
+}
+
+/// Start and due date of a task
+type TaskDates = (Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+/// Parse `--date` (setting both dates), or `--start` and `--due` with `parse`
+fn parse_date_flags(
+    date: Option<String>,
+    start: Option<String>,
+    due: Option<String>,
+    parse: impl Fn(&str) -> Result<DateTime<Utc>, DateParseError>,
+) -> anyhow::Result<TaskDates> {
     if let Some(date_str) = date {
-        let dt = parse_date(&date_str)?;
-        let formatted = dt.format("%Y-%m-%dT%H:%M:%S%z").to_string();
-        return Ok((Some(formatted.clone()), Some(formatted)));
+        let dt = parse(&date_str)?;
+        return Ok((Some(dt), Some(dt)));
     }
 
-    let start_date = start
-        .map(|s| parse_date(&s).map(|dt| dt.format("%Y-%m-%dT%H:%M:%S%z").to_string()))
-        .transpose()?;
-
-    let due_date = due
-        .map(|s| parse_date(&s).map(|dt| dt.format("%Y-%m-%dT%H:%M:%S%z").to_string()))
-        .transpose()?;
+    let start_date = start.map(|s| parse(&s)).transpose()?;
+    let due_date = due.map(|s| parse(&s)).transpose()?;
 
     Ok((start_date, due_date))
 }
@@ -985,8 +1856,10 @@ async fn cmd_subtask_list(
     format: OutputFormat,
     quiet: bool,
 ) -> anyhow::Result<()> {
-    let project_id = get_project_id(project_id, project_name).await?;
-    let client = TickTickClient::new()?;
+    let config = Config::load()?;
+    let project = project_arg(&config, project_id, project_name)?;
+    let client = TickTickClient::from_config(&config)?;
+    let project_id = get_project_id(&config, &client, project, MatchMode::Fuzzy).await?;
     let task = client.get_task(&project_id, task_id).await?;
 
     let subtasks = task.items;
@@ -2,6 +2,10 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use super::{ChecklistItem, ChecklistItemRequest, Priority, Status};
+use crate::config::TaskDefaultValues;
+
+/// Date format expected by the TickTick API, e.g. "2026-01-15T14:00:00+0000"
+pub const API_DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%z";
 
 /// Task model matching TickTick API format
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,7 +53,8 @@ impl Task {
 ///
 /// Use this builder when you need to construct a task programmatically
 /// with optional fields. The builder provides sensible defaults for
-/// all optional fields.
+/// all optional fields, which can be replaced by configured task defaults
+/// with [`defaults()`](Self::defaults).
 ///
 /// # Required Fields
 ///
@@ -84,14 +89,15 @@ impl Task {
 pub struct TaskBuilder {
     project_id: String,
     title: String,
-    is_all_day: bool,
+    is_all_day: Option<bool>,
     content: Option<String>,
     due_date: Option<DateTime<Utc>>,
-    priority: Priority,
+    priority: Option<Priority>,
     start_date: Option<DateTime<Utc>>,
     time_zone: Option<String>,
     tags: Vec<String>,
     items: Vec<ChecklistItemRequest>,
+    defaults: TaskDefaultValues,
 }
 
 #[allow(dead_code)] // Builder methods available for external use; tested
@@ -118,7 +124,7 @@ impl TaskBuilder {
 
     /// Set the task priority level.
     pub fn priority(mut self, priority: Priority) -> Self {
-        self.priority = priority;
+        self.priority = Some(priority);
         self
     }
 
@@ -136,7 +142,7 @@ impl TaskBuilder {
 
     /// Set whether this is an all-day task (no specific time).
     pub fn all_day(mut self, is_all_day: bool) -> Self {
-        self.is_all_day = is_all_day;
+        self.is_all_day = Some(is_all_day);
         self
     }
 
@@ -173,6 +179,51 @@ impl TaskBuilder {
         self
     }
 
+    /// Use task defaults, e.g. from
+    /// [`TaskDefaults::for_project()`](crate::config::TaskDefaults::for_project),
+    /// for the priority, all-day flag and timezone unless they are set
+    /// explicitly, and add the default tags to the task's tags.
+    ///
+    /// The default time of day applies when parsing dates given without a
+    /// time (see [`parse_date_at()`](crate::utils::date_parser::parse_date_at)),
+    /// so it's not used here.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ticktickrs::config::TaskDefaultValues;
+    /// use ticktickrs::models::task::TaskBuilder;
+    /// use ticktickrs::models::Priority;
+    ///
+    /// let defaults = TaskDefaultValues {
+    ///     priority: Some(Priority::Medium),
+    ///     tags: vec!["work".to_string()],
+    ///     ..Default::default()
+    /// };
+    /// let task = TaskBuilder::new("proj123", "Review PR")
+    ///     .priority(Priority::High)
+    ///     .tags(vec!["review".to_string()])
+    ///     .defaults(defaults)
+    ///     .build();
+    ///
+    /// assert_eq!(task.priority, Priority::High);
+    /// assert_eq!(task.tags, vec!["review", "work"]);
+    /// ```
+    pub fn defaults(mut self, defaults: TaskDefaultValues) -> Self {
+        self.defaults = defaults;
+        self
+    }
+
+    /// Fill unset fields from the task defaults
+    fn apply_defaults(mut self) -> Self {
+        let defaults = std::mem::take(&mut self.defaults);
+        self.priority = self.priority.or(defaults.priority);
+        self.is_all_day = self.is_all_day.or(defaults.all_day);
+        self.time_zone = self.time_zone.or(defaults.timezone);
+        add_tags(&mut self.tags, defaults.tags);
+        self
+    }
+
     /// Build the [`Task`] instance.
     ///
     /// The returned task will have an empty `id` field, which will be
@@ -182,23 +233,24 @@ impl TaskBuilder {
     /// built Task. Use [`into_create_request()`](Self::into_create_request) to
     /// create a request that includes subtasks for the API.
     pub fn build(self) -> Task {
+        let builder = self.apply_defaults();
         Task {
             id: String::new(), // Will be set by API
-            project_id: self.project_id,
-            title: self.title,
-            is_all_day: self.is_all_day,
+            project_id: builder.project_id,
+            title: builder.title,
+            is_all_day: builder.is_all_day.unwrap_or(false),
             completed_time: None,
-            content: self.content.unwrap_or_default(),
-            due_date: self.due_date,
+            content: builder.content.unwrap_or_default(),
+            due_date: builder.due_date,
             items: Vec::new(),
-            priority: self.priority,
+            priority: builder.priority.unwrap_or_default(),
             reminders: Vec::new(),
             repeat_flag: None,
             sort_order: 0,
-            start_date: self.start_date,
+            start_date: builder.start_date,
             status: Status::Normal,
-            time_zone: self.time_zone.unwrap_or_default(),
-            tags: self.tags,
+            time_zone: builder.time_zone.unwrap_or_default(),
+            tags: builder.tags,
         }
     }
 
@@ -207,7 +259,7 @@ impl TaskBuilder {
     /// This method creates a request that can be passed to
     /// [`TickTickClient::create_task()`](crate::api::TickTickClient::create_task).
     /// Unlike [`build()`](Self::build), this includes subtasks set via
-    /// [`items()`](Self::items).
+    /// [`items()`](Self::items). Dates are formatted with [`API_DATE_FORMAT`].
     ///
     /// # Example
     ///
@@ -225,36 +277,50 @@ impl TaskBuilder {
     /// // Now use: client.create_task(&request).await
     /// ```
     pub fn into_create_request(self) -> crate::api::CreateTaskRequest {
+        let builder = self.apply_defaults();
         crate::api::CreateTaskRequest {
-            title: self.title,
-            project_id: self.project_id,
-            content: self.content,
-            is_all_day: if self.is_all_day { Some(true) } else { None },
-            start_date: self.start_date.map(|d| d.to_rfc3339()),
-            due_date: self.due_date.map(|d| d.to_rfc3339()),
-            priority: if self.priority != Priority::None {
-                Some(self.priority.to_api_value())
-            } else {
-                None
-            },
-            time_zone: self.time_zone,
-            tags: if self.tags.is_empty() {
+            title: builder.title,
+            project_id: builder.project_id,
+            content: builder.content,
+            is_all_day: builder.is_all_day.filter(|&all_day| all_day),
+            start_date: builder
+                .start_date
+                .map(|d| d.format(API_DATE_FORMAT).to_string()),
+            due_date: builder
+                .due_date
+                .map(|d| d.format(API_DATE_FORMAT).to_string()),
+            priority: builder
+                .priority
+                .filter(|&p| p != Priority::None)
+                .map(Priority::to_api_value),
+            time_zone: builder.time_zone,
+            tags: if builder.tags.is_empty() {
                 None
             } else {
-                Some(self.tags)
+                Some(builder.tags)
             },
-            items: if self.items.is_empty() {
+            items: if builder.items.is_empty() {
                 None
             } else {
-                Some(self.items)
+                Some(builder.items)
             },
         }
     }
 }
 
+/// Add `extra` tags to `tags`, skipping ones already present (ignoring case)
+pub(crate) fn add_tags(tags: &mut Vec<String>, extra: impl IntoIterator<Item = String>) {
+    for tag in extra {
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+            tags.push(tag);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
 
     #[test]
     fn test_task_deserialization() {
@@ -299,6 +365,43 @@ mod tests {
         assert_eq!(task.tags, vec!["test"]);
     }
 
+    #[test]
+    fn test_task_builder_defaults() {
+        let defaults = TaskDefaultValues {
+            timezone: Some("Europe/Amsterdam".to_string()),
+            priority: Some(Priority::Medium),
+            tags: vec!["work".to_string(), "Urgent".to_string()],
+            all_day: Some(true),
+            time: None,
+        };
+
+        // Defaults fill unset fields and add tags
+        let task = TaskBuilder::new("proj123", "New Task")
+            .tags(vec!["urgent".to_string()])
+            .defaults(defaults.clone())
+            .build();
+        assert_eq!(task.priority, Priority::Medium);
+        assert!(task.is_all_day);
+        assert_eq!(task.time_zone, "Europe/Amsterdam");
+        assert_eq!(task.tags, vec!["urgent", "work"]);
+
+        // Explicit values win, whether set before or after the defaults
+        let request = TaskBuilder::new("proj123", "New Task")
+            .defaults(defaults)
+            .priority(Priority::None)
+            .all_day(false)
+            .time_zone("UTC")
+            .due_date(Utc.with_ymd_and_hms(2026, 1, 15, 14, 0, 0).unwrap())
+            .into_create_request();
+        assert_eq!(request.priority, None);
+        assert_eq!(request.is_all_day, None);
+        assert_eq!(request.time_zone.as_deref(), Some("UTC"));
+        assert_eq!(
+            request.due_date.as_deref(),
+            Some("2026-01-15T14:00:00+0000")
+        );
+    }
+
     #[test]
     fn test_task_special_characters_in_title() {
         let json = r#"{
@@ -1,5 +1,12 @@
 use serde::{Deserialize, Serialize};
 
+use crate::api::project_names::ProjectNameError;
+use crate::api::project_names::{inexact_matches, InexactMatch};
+use crate::api::timings::{self, TimingReport};
+use crate::api::ApiError;
+use crate::utils::date_parser::DateParseError;
+use crate::utils::error::ErrorCode;
+
 /// Standard JSON response wrapper for all commands
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JsonResponse<T> {
@@ -52,7 +59,6 @@ impl<T: Serialize> JsonResponse<T> {
 
 impl<T> JsonResponse<T> {
     /// Create an error response
-    #[allow(dead_code)] // Available for external use
     pub fn error(code: impl Into<String>, message: impl Into<String>) -> Self {
         Self {
             success: false,
@@ -67,7 +73,6 @@ impl<T> JsonResponse<T> {
     }
 
     /// Create an error response with additional details
-    #[allow(dead_code)] // Available for external use
     pub fn error_with_details(
         code: impl Into<String>,
         message: impl Into<String>,
@@ -86,10 +91,45 @@ impl<T> JsonResponse<T> {
     }
 }
 
+/// Additional information about how a response was produced
+#[derive(Debug, Clone, Serialize)]
+pub struct ResponseMeta {
+    /// Requests made by the command (with `--timings`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timings: Option<TimingReport>,
+    /// Project names resolved by prefix or fuzzy match
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub project_matches: Vec<InexactMatch>,
+}
+
+/// A response together with its `meta` object
+#[derive(Serialize)]
+struct WithMeta<'a, T> {
+    #[serde(flatten)]
+    response: &'a JsonResponse<T>,
+    meta: ResponseMeta,
+}
+
 impl<T: Serialize> JsonResponse<T> {
     /// Convert response to JSON string with pretty printing
+    ///
+    /// With `--timings`, the request timing report is included as
+    /// `meta.timings`; project names that weren't matched exactly are listed
+    /// in `meta.project_matches`.
     pub fn to_json_string(&self) -> String {
-        serde_json::to_string_pretty(self).unwrap_or_else(|e| {
+        let meta = ResponseMeta {
+            timings: timings::report(),
+            project_matches: inexact_matches(),
+        };
+        let json = if meta.timings.is_none() && meta.project_matches.is_empty() {
+            serde_json::to_string_pretty(self)
+        } else {
+            serde_json::to_string_pretty(&WithMeta {
+                response: self,
+                meta,
+            })
+        };
+        json.unwrap_or_else(|e| {
             format!(
                 r#"{{"success":false,"error":{{"code":"SERIALIZATION_ERROR","message":"{}"}}}}"#,
                 e
@@ -98,6 +138,37 @@ impl<T: Serialize> JsonResponse<T> {
     }
 }
 
+/// Build a JSON error response for an error returned by a command.
+///
+/// API errors keep their machine-readable code and any details (such as
+/// the number of attempts made); other errors are reported as `UNKNOWN`.
+pub fn error_response(err: &anyhow::Error) -> JsonResponse<()> {
+    let message = err.to_string();
+    let code = error_code(err).to_string();
+
+    let details = match err.downcast_ref::<ProjectNameError>() {
+        Some(name_err) => name_err.details(),
+        None => err.downcast_ref::<ApiError>().and_then(ApiError::details),
+    };
+    match details {
+        Some(details) => JsonResponse::error_with_details(code, message, details),
+        None => JsonResponse::error(code, message),
+    }
+}
+
+/// Machine-readable code for an error
+pub fn error_code(err: &anyhow::Error) -> ErrorCode {
+    if let Some(api_err) = err.downcast_ref::<ApiError>() {
+        api_err.code()
+    } else if let Some(name_err) = err.downcast_ref::<ProjectNameError>() {
+        name_err.code()
+    } else if err.downcast_ref::<DateParseError>().is_some() {
+        ErrorCode::InvalidDate
+    } else {
+        ErrorCode::Unknown
+    }
+}
+
 /// Convert a Result to a JSON response string
 #[allow(dead_code)] // Available for external use
 pub fn result_to_json<T: Serialize, E: std::fmt::Display>(result: Result<T, E>) -> String {
@@ -151,6 +222,78 @@ pub struct SubtaskListData {
     pub count: usize,
 }
 
+/// Data wrapper for profile list output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileListData {
+    pub profiles: Vec<crate::config::profile::ProfileInfo>,
+    pub active: String,
+}
+
+/// Data wrapper for single profile output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileData {
+    pub profile: crate::config::profile::ProfileInfo,
+}
+
+/// Data wrapper for auth status output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthStatusData {
+    /// Active profile
+    pub profile: String,
+    /// Whether the live API check succeeded
+    pub authenticated: bool,
+    /// Whether a human has to run `tickrs init` again
+    pub needs_reauth: bool,
+    /// Whether mutating commands are refused (read-only mode or a token
+    /// without `tasks:write`)
+    pub read_only: bool,
+    #[serde(flatten)]
+    pub token: crate::config::TokenStatus,
+    /// Result of listing projects with the token, if one was found
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub live_check: Option<LiveCheck>,
+}
+
+/// Result of a live API request made to verify a token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveCheck {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Data wrapper for auth export output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthExportData {
+    pub tokens: crate::config::TokenSet,
+}
+
+/// Data wrapper for config list output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigListData {
+    /// Active profile
+    pub profile: String,
+    /// Path of the config file
+    pub path: String,
+    /// Path of the directory-local `.tickrs.toml`, if one applies
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_path: Option<String>,
+    pub settings: Vec<crate::config::keys::ConfigEntry>,
+}
+
+/// Data wrapper for config path output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigPathData {
+    pub path: String,
+    /// Whether the file exists yet
+    pub exists: bool,
+    /// Path of the directory-local `.tickrs.toml`, if one applies
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_path: Option<String>,
+}
+
 /// Data wrapper for version output
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VersionData {
@@ -213,6 +356,49 @@ mod tests {
         assert!(json_str.contains("\"name\": \"Test\""));
     }
 
+    #[test]
+    fn test_error_response_from_api_error() {
+        let err = anyhow::Error::new(ApiError::Retried {
+            attempts: 2,
+            source: Box::new(ApiError::ServerError("503".into())),
+        });
+        let response = error_response(&err);
+        let error = response.error.unwrap();
+        assert_eq!(error.code, "SERVER_ERROR");
+        assert_eq!(error.details.unwrap()["attempts"], 2);
+    }
+
+    #[test]
+    fn test_error_response_includes_upstream_error() {
+        let err = anyhow::Error::new(ApiError::NotFound(crate::api::ErrorInfo {
+            message: "Project not found".to_string(),
+            error_code: Some("project_not_found".to_string()),
+            status: Some(404),
+            method: Some("GET".to_string()),
+            endpoint: Some("/project/abc".to_string()),
+        }));
+        let error = error_response(&err).error.unwrap();
+        assert_eq!(error.code, "NOT_FOUND");
+        assert_eq!(error.message, "Resource not found: Project not found");
+
+        let details = error.details.unwrap();
+        assert_eq!(details["error_code"], "project_not_found");
+        assert_eq!(details["status"], 404);
+        assert_eq!(details["method"], "GET");
+        assert_eq!(details["endpoint"], "/project/abc");
+        assert!(details.get("message").is_none());
+    }
+
+    #[test]
+    fn test_error_response_from_other_error() {
+        let err = anyhow::anyhow!("Something went wrong");
+        let response = error_response(&err);
+        let error = response.error.unwrap();
+        assert_eq!(error.code, "UNKNOWN");
+        assert_eq!(error.message, "Something went wrong");
+        assert!(error.details.is_none());
+    }
+
     #[test]
     fn test_result_to_json_success() {
         let result: Result<_, &str> = Ok(json!({"id": "123"}));
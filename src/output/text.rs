@@ -1,4 +1,8 @@
+use crate::config::keys::ValueSource;
+use crate::config::profile::ProfileInfo;
+use crate::config::TokenSource;
 use crate::models::{ChecklistItem, Priority, Project, Task};
+use crate::output::json::{AuthStatusData, ConfigListData};
 
 /// Format a list of projects for text output
 pub fn format_project_list(projects: &[Project]) -> String {
@@ -147,6 +151,137 @@ fn format_subtask_line(subtask: &ChecklistItem) -> String {
     format!("  {} {}", status_marker, subtask.title)
 }
 
+/// Format a list of profiles for text output, marking the active one with `*`
+pub fn format_profile_list(profiles: &[ProfileInfo]) -> String {
+    let mut output = String::from("Profiles:\n");
+    for profile in profiles {
+        let marker = if profile.active { '*' } else { ' ' };
+        let mut details = vec![if profile.authenticated {
+            "authenticated".to_string()
+        } else {
+            "not authenticated".to_string()
+        }];
+        if let Some(project_id) = &profile.default_project_id {
+            details.push(format!("default project {}", project_id));
+        }
+        output.push_str(&format!(
+            "{} {} ({})\n",
+            marker,
+            profile.name,
+            details.join(", ")
+        ));
+    }
+    output.push_str(&format!("\nTotal: {} profile(s)", profiles.len()));
+    output
+}
+
+/// Format the report of `tickrs auth status`
+pub fn format_auth_status(status: &AuthStatusData) -> String {
+    let token = &status.token;
+    let mut output = format!("Profile:     {}\n", status.profile);
+    let source = match token.source {
+        TokenSource::Env => format!("{} environment variable", crate::constants::ENV_TOKEN),
+        TokenSource::Storage => "token storage".to_string(),
+        TokenSource::None => "none".to_string(),
+    };
+    output.push_str(&format!("Source:      {}\n", source));
+    output.push_str(&format!(
+        "Storage:     {} ({})\n",
+        token.location, token.backend
+    ));
+    if let Some(permissions) = &token.permissions {
+        output.push_str(&format!("Permissions: {}\n", permissions));
+    }
+    if token.source == TokenSource::Storage {
+        let scopes = if token.scopes.is_empty() {
+            "unknown".to_string()
+        } else {
+            token.scopes.join(" ")
+        };
+        output.push_str(&format!("Scopes:      {}\n", scopes));
+        let expiry = match token.expires_at {
+            Some(expires_at) if token.expired => {
+                format!("expired {} UTC", expires_at.format("%Y-%m-%d %H:%M:%S"))
+            }
+            Some(expires_at) => format!("{} UTC", expires_at.format("%Y-%m-%d %H:%M:%S")),
+            None => "unknown".to_string(),
+        };
+        output.push_str(&format!("Expires:     {}\n", expiry));
+        output.push_str(&format!(
+            "Refresh:     {}\n",
+            if token.refreshable {
+                "available"
+            } else {
+                "not available"
+            }
+        ));
+    }
+    if status.read_only {
+        output.push_str("Access:      read-only\n");
+    }
+    if let Some(error) = &token.error {
+        output.push_str(&format!("Error:       {}\n", error));
+    }
+    if let Some(check) = &status.live_check {
+        let result = match &check.error {
+            Some(error) => format!("failed: {}", error),
+            None => "OK".to_string(),
+        };
+        output.push_str(&format!("API check:   {}\n", result));
+    }
+    for warning in &token.warnings {
+        output.push_str(&format!("Warning: {}\n", warning));
+    }
+    if status.needs_reauth {
+        output.push_str("\nRun 'tickrs init' to authenticate.\n");
+    }
+    output.trim_end().to_string()
+}
+
+/// Format a config value as printed by `tickrs config get`
+pub fn format_config_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(items) => items
+            .iter()
+            .map(|item| {
+                item.as_str()
+                    .map_or_else(|| item.to_string(), str::to_string)
+            })
+            .collect::<Vec<_>>()
+            .join(","),
+        other => other.to_string(),
+    }
+}
+
+/// Format all settings, marking those not taken from the config file
+pub fn format_config_list(list: &ConfigListData) -> String {
+    let mut output = format!(
+        "Profile:     {}\nConfig file: {}\n",
+        list.profile, list.path
+    );
+    if let Some(local_path) = &list.local_path {
+        output.push_str(&format!("Local file:  {}\n", local_path));
+    }
+    output.push('\n');
+    for entry in &list.settings {
+        let value = if entry.value.is_null() {
+            "(not set)".to_string()
+        } else {
+            entry.value.to_string()
+        };
+        let marker = match entry.source {
+            ValueSource::Default if !entry.value.is_null() => " (default)".to_string(),
+            ValueSource::Local => " (local)".to_string(),
+            ValueSource::Env => format!(" (from {})", entry.env),
+            _ => String::new(),
+        };
+        output.push_str(&format!("{} = {}{}\n", entry.key, value, marker));
+    }
+    output.trim_end().to_string()
+}
+
 /// Format a success message
 pub fn format_success(message: &str) -> String {
     format!("OK: {}", message)
@@ -219,6 +354,119 @@ mod tests {
         assert!(output.contains("Total: 2 project(s)"));
     }
 
+    #[test]
+    fn test_format_profile_list() {
+        let profiles = vec![
+            ProfileInfo {
+                name: "default".to_string(),
+                active: false,
+                authenticated: true,
+                default_project_id: Some("proj123".to_string()),
+            },
+            ProfileInfo {
+                name: "work".to_string(),
+                active: true,
+                authenticated: false,
+                default_project_id: None,
+            },
+        ];
+        let output = format_profile_list(&profiles);
+        assert!(output.contains("  default (authenticated, default project proj123)"));
+        assert!(output.contains("* work (not authenticated)"));
+        assert!(output.contains("Total: 2 profile(s)"));
+    }
+
+    #[test]
+    fn test_format_config_list() {
+        use crate::config::keys::ConfigEntry;
+
+        let entries = vec![
+            ConfigEntry {
+                key: "default_project_id".to_string(),
+                env: "TICKRS_DEFAULT_PROJECT".to_string(),
+                value: serde_json::Value::Null,
+                source: ValueSource::Default,
+            },
+            ConfigEntry {
+                key: "default_project_color".to_string(),
+                env: "TICKRS_DEFAULT_PROJECT_COLOR".to_string(),
+                value: serde_json::json!("#00FF00"),
+                source: ValueSource::Local,
+            },
+            ConfigEntry {
+                key: "retry.max_attempts".to_string(),
+                env: "TICKRS_RETRY_MAX_ATTEMPTS".to_string(),
+                value: serde_json::json!(3),
+                source: ValueSource::Default,
+            },
+            ConfigEntry {
+                key: "cache.enabled".to_string(),
+                env: "TICKRS_CACHE".to_string(),
+                value: serde_json::json!(false),
+                source: ValueSource::Env,
+            },
+        ];
+        let list = ConfigListData {
+            profile: "work".to_string(),
+            path: "/tmp/config.toml".to_string(),
+            local_path: Some("/repo/.tickrs.toml".to_string()),
+            settings: entries,
+        };
+        let output = format_config_list(&list);
+        assert!(output.starts_with("Profile:     work\nConfig file: /tmp/config.toml\n"));
+        assert!(output.contains("Local file:  /repo/.tickrs.toml\n\n"));
+        assert!(output.contains("default_project_id = (not set)\n"));
+        assert!(output.contains("default_project_color = \"#00FF00\" (local)\n"));
+        assert!(output.contains("retry.max_attempts = 3 (default)\n"));
+        assert!(output.ends_with("cache.enabled = false (from TICKRS_CACHE)"));
+
+        assert_eq!(
+            format_config_value(&serde_json::json!("#00FF00")),
+            "#00FF00"
+        );
+        assert_eq!(format_config_value(&serde_json::json!(["a", "b"])), "a,b");
+        assert_eq!(format_config_value(&serde_json::Value::Null), "");
+    }
+
+    #[test]
+    fn test_format_auth_status() {
+        use crate::config::{SecretBackend, TokenStatus};
+        use crate::output::json::LiveCheck;
+
+        let status = AuthStatusData {
+            profile: "work".to_string(),
+            authenticated: false,
+            needs_reauth: true,
+            read_only: true,
+            token: TokenStatus {
+                source: TokenSource::Storage,
+                backend: SecretBackend::File,
+                location: "/home/me/.local/share/tickrs/token".to_string(),
+                permissions: Some("0600".to_string()),
+                scopes: vec!["tasks:read".to_string(), "tasks:write".to_string()],
+                expires_at: None,
+                expired: false,
+                refreshable: false,
+                error: None,
+                warnings: Vec::new(),
+            },
+            live_check: Some(LiveCheck {
+                ok: false,
+                error_code: Some("AUTH_EXPIRED".to_string()),
+                error: Some("Invalid or expired token".to_string()),
+            }),
+        };
+        let output = format_auth_status(&status);
+        assert!(output.contains("Profile:     work"));
+        assert!(output.contains("Source:      token storage"));
+        assert!(output.contains("Permissions: 0600"));
+        assert!(output.contains("Scopes:      tasks:read tasks:write"));
+        assert!(output.contains("Expires:     unknown"));
+        assert!(output.contains("Access:      read-only"));
+        assert!(output.contains("API check:   failed: Invalid or expired token"));
+        assert!(output.ends_with("Run 'tickrs init' to authenticate."));
+    }
+
     #[test]
     fn test_format_empty_project_list() {
         let output = format_project_list(&[]);
@@ -6,7 +6,7 @@
 //! - Time specifications: "tomorrow at 2pm"
 //! - ISO 8601 formats
 
-use chrono::{DateTime, Duration, Local, NaiveTime, TimeZone, Utc};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc};
 use chrono_tz::Tz;
 use thiserror::Error;
 
@@ -19,7 +19,6 @@ pub enum DateParseError {
     InvalidFormat(String),
 
     #[error("Invalid timezone: '{0}'")]
-    #[allow(dead_code)] // Used by parse_date_with_timezone
     InvalidTimezone(String),
 
     #[error("Date is in the past: '{0}'")]
@@ -86,6 +85,62 @@ pub fn parse_date(input: &str) -> Result<DateTime<Utc>, DateParseError> {
     dateparser::parse(input).map_err(|_| DateParseError::InvalidFormat(input.to_string()))
 }
 
+/// Parse a date string, putting dates given without a time at `time` of day
+///
+/// Date-only inputs are "today", "tomorrow", "yesterday", "next week",
+/// "next month" and "2025-01-15". They are taken as dates in `timezone`
+/// (UTC if not given); all other inputs are parsed with [`parse_date`].
+///
+/// # Arguments
+/// * `input` - The date string to parse
+/// * `time` - Time of day for date-only inputs
+/// * `timezone` - The timezone name (e.g., "America/New_York")
+///
+/// # Returns
+/// * `Ok(DateTime<Utc>)` - The parsed date converted to UTC
+/// * `Err(DateParseError)` - If parsing fails or the timezone is invalid
+pub fn parse_date_at(
+    input: &str,
+    time: NaiveTime,
+    timezone: Option<&str>,
+) -> Result<DateTime<Utc>, DateParseError> {
+    let tz: Tz = match timezone {
+        Some(name) => name
+            .parse()
+            .map_err(|_| DateParseError::InvalidTimezone(name.to_string()))?,
+        None => Tz::UTC,
+    };
+
+    let today = Utc::now().with_timezone(&tz).date_naive();
+    let Some(date) = parse_date_only(input, today) else {
+        return parse_date(input);
+    };
+
+    let naive_dt = date.and_time(time);
+    // Use the earlier time when clocks go back, and skip the gap when they go forward
+    let local_dt = tz
+        .from_local_datetime(&naive_dt)
+        .earliest()
+        .or_else(|| {
+            tz.from_local_datetime(&(naive_dt + Duration::hours(1)))
+                .earliest()
+        })
+        .ok_or_else(|| DateParseError::InvalidFormat(input.to_string()))?;
+    Ok(local_dt.with_timezone(&Utc))
+}
+
+/// The date of inputs that name a day without a time
+fn parse_date_only(input: &str, today: NaiveDate) -> Option<NaiveDate> {
+    match input.trim().to_lowercase().as_str() {
+        "today" => Some(today),
+        "tomorrow" => Some(today + Duration::days(1)),
+        "yesterday" => Some(today - Duration::days(1)),
+        "next week" => Some(today + Duration::weeks(1)),
+        "next month" => Some(today + Duration::days(30)),
+        other => NaiveDate::parse_from_str(other, "%Y-%m-%d").ok(),
+    }
+}
+
 /// Parse relative time expressions like "3 days", "2 hours", "30 minutes"
 fn parse_relative_time(input: &str, base: DateTime<Utc>) -> Option<DateTime<Utc>> {
     let parts: Vec<&str> = input.split_whitespace().collect();
@@ -270,6 +325,35 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_parse_date_at_time_of_day() {
+        let nine = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+
+        let dt = parse_date_at("2026-01-15", nine, None).unwrap();
+        assert_eq!(dt, Utc.with_ymd_and_hms(2026, 1, 15, 9, 0, 0).unwrap());
+
+        // 09:00 in Amsterdam (CET, UTC+1) is 08:00 UTC
+        let dt = parse_date_at("2026-01-15", nine, Some("Europe/Amsterdam")).unwrap();
+        assert_eq!(dt, Utc.with_ymd_and_hms(2026, 1, 15, 8, 0, 0).unwrap());
+
+        let dt = parse_date_at("Tomorrow", nine, None).unwrap();
+        assert_eq!(dt.date_naive(), Utc::now().date_naive() + Duration::days(1));
+        assert_eq!(dt.time(), nine);
+    }
+
+    #[test]
+    fn test_parse_date_at_keeps_explicit_times() {
+        let nine = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+
+        let dt = parse_date_at("2026-01-15T14:30:00Z", nine, None).unwrap();
+        assert_eq!(dt, Utc.with_ymd_and_hms(2026, 1, 15, 14, 30, 0).unwrap());
+
+        assert!(matches!(
+            parse_date_at("today", nine, Some("Invalid/Timezone")),
+            Err(DateParseError::InvalidTimezone(_))
+        ));
+    }
+
     #[test]
     fn test_parse_invalid_timezone() {
         let result = parse_date_with_timezone("2025-01-15", "Invalid/Timezone");
@@ -34,6 +34,8 @@ pub enum ErrorCode {
     InvalidDate,
     /// Project not specified and no default set
     NoProject,
+    /// Command would modify data in read-only mode
+    ReadOnly,
     /// Unknown or unspecified error
     Unknown,
 }
@@ -52,6 +54,7 @@ impl fmt::Display for ErrorCode {
             ErrorCode::ConfigError => "CONFIG_ERROR",
             ErrorCode::InvalidDate => "INVALID_DATE",
             ErrorCode::NoProject => "NO_PROJECT",
+            ErrorCode::ReadOnly => "READ_ONLY",
             ErrorCode::Unknown => "UNKNOWN",
         };
         write!(f, "{}", code)
@@ -95,6 +98,9 @@ pub enum AppError {
     #[error("No project specified. Use --project-id, --project-name, or run 'tickrs project use <name>' to set a default.")]
     NoProject,
 
+    #[error("Refusing to modify data: {0}")]
+    ReadOnly(String),
+
     #[error("{0}")]
     Other(String),
 }
@@ -115,6 +121,7 @@ impl AppError {
             AppError::ConfigError(_) => ErrorCode::ConfigError,
             AppError::InvalidDate(_) => ErrorCode::InvalidDate,
             AppError::NoProject => ErrorCode::NoProject,
+            AppError::ReadOnly(_) => ErrorCode::ReadOnly,
             AppError::Other(_) => ErrorCode::Unknown,
         }
     }
@@ -133,6 +140,7 @@ impl AppError {
             ErrorCode::ConfigError => "CONFIG_ERROR",
             ErrorCode::InvalidDate => "INVALID_DATE",
             ErrorCode::NoProject => "NO_PROJECT",
+            ErrorCode::ReadOnly => "READ_ONLY",
             ErrorCode::Unknown => "UNKNOWN",
         }
     }
@@ -143,13 +151,17 @@ impl From<crate::api::ApiError> for AppError {
     fn from(err: crate::api::ApiError) -> Self {
         match err {
             crate::api::ApiError::NotAuthenticated => AppError::AuthRequired,
-            crate::api::ApiError::Unauthorized => AppError::AuthExpired,
-            crate::api::ApiError::NotFound(resource) => AppError::NotFound(resource),
-            crate::api::ApiError::BadRequest(msg) => AppError::InvalidRequest(msg),
-            crate::api::ApiError::RateLimited => AppError::RateLimited,
-            crate::api::ApiError::ServerError(msg) => AppError::ServerError(msg),
+            crate::api::ApiError::Unauthorized | crate::api::ApiError::TokenRefreshFailed(_) => {
+                AppError::AuthExpired
+            }
+            crate::api::ApiError::NotFound(info) => AppError::NotFound(info.message),
+            crate::api::ApiError::BadRequest(info) => AppError::InvalidRequest(info.message),
+            crate::api::ApiError::RateLimited { .. } => AppError::RateLimited,
+            crate::api::ApiError::ServerError(info) => AppError::ServerError(info.message),
             crate::api::ApiError::NetworkError(e) => AppError::NetworkError(e.to_string()),
             crate::api::ApiError::ParseError(msg) => AppError::ParseError(msg),
+            crate::api::ApiError::ReadOnly(reason) => AppError::ReadOnly(reason.to_string()),
+            crate::api::ApiError::Retried { source, .. } => AppError::from(*source),
         }
     }
 }
@@ -252,7 +264,7 @@ mod tests {
 
     #[test]
     fn test_from_api_error_not_found() {
-        let api_err = crate::api::ApiError::NotFound("Task".to_string());
+        let api_err = crate::api::ApiError::NotFound("Task".into());
         let app_err: AppError = api_err.into();
         match &app_err {
             AppError::NotFound(resource) => assert_eq!(resource, "Task"),
@@ -263,7 +275,7 @@ mod tests {
 
     #[test]
     fn test_from_api_error_bad_request() {
-        let api_err = crate::api::ApiError::BadRequest("Invalid field".to_string());
+        let api_err = crate::api::ApiError::BadRequest("Invalid field".into());
         let app_err: AppError = api_err.into();
         match app_err {
             AppError::InvalidRequest(msg) => assert_eq!(msg, "Invalid field"),
@@ -273,15 +285,25 @@ mod tests {
 
     #[test]
     fn test_from_api_error_rate_limited() {
-        let api_err = crate::api::ApiError::RateLimited;
+        let api_err = crate::api::ApiError::RateLimited { retry_after: None };
         let app_err: AppError = api_err.into();
         assert!(matches!(app_err, AppError::RateLimited));
         assert_eq!(app_err.code(), ErrorCode::RateLimited);
     }
 
+    #[test]
+    fn test_from_api_error_retried_unwraps_source() {
+        let api_err = crate::api::ApiError::Retried {
+            attempts: 3,
+            source: Box::new(crate::api::ApiError::ServerError("503".into())),
+        };
+        let app_err: AppError = api_err.into();
+        assert!(matches!(app_err, AppError::ServerError(_)));
+    }
+
     #[test]
     fn test_from_api_error_server_error() {
-        let api_err = crate::api::ApiError::ServerError("500 Internal".to_string());
+        let api_err = crate::api::ApiError::ServerError("500 Internal".into());
         let app_err: AppError = api_err.into();
         match app_err {
             AppError::ServerError(msg) => assert_eq!(msg, "500 Internal"),
@@ -362,6 +384,7 @@ mod tests {
             ErrorCode::ConfigError,
             ErrorCode::InvalidDate,
             ErrorCode::NoProject,
+            ErrorCode::ReadOnly,
             ErrorCode::Unknown,
         ];
 
@@ -0,0 +1,244 @@
+//! Logging setup with secret redaction
+//!
+//! Diagnostics are written to stderr depending on `-v`/`-vv` (or `RUST_LOG`),
+//! and optionally as JSON lines to a log file (`--log-file`). Every log line
+//! passes through [`redact`] before it is written, so bearer tokens, OAuth
+//! codes and client secrets never end up in a terminal or on disk.
+
+use std::borrow::Cow;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{OnceLock, RwLock};
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+use crate::constants::{DEFAULT_LOG_LEVEL, ENV_LOG_LEVEL};
+
+/// Placeholder for redacted values
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Secrets known at runtime (tokens, client secrets), redacted verbatim
+fn known_secrets() -> &'static RwLock<Vec<String>> {
+    static SECRETS: OnceLock<RwLock<Vec<String>>> = OnceLock::new();
+    SECRETS.get_or_init(|| RwLock::new(Vec::new()))
+}
+
+/// Patterns for secrets that may appear in logged URLs, headers and bodies
+fn secret_patterns() -> &'static [Regex] {
+    static PATTERNS: OnceLock<Vec<Regex>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            // Authorization: Bearer <token> / Basic <credentials>
+            r"(?i)\b((?:bearer|basic)\s+)[A-Za-z0-9\-._~+/]+=*",
+            // key=value (query strings, form bodies) and "key": "value" (JSON)
+            r#"(?i)\b((?:access_token|refresh_token|id_token|client_secret|code_verifier|code|password)"?\s*[:=]\s*"?)[^"&\s,}]+"#,
+        ]
+        .iter()
+        .map(|pattern| Regex::new(pattern).expect("valid redaction pattern"))
+        .collect()
+    })
+}
+
+/// Register a secret value (e.g. an access token) to be redacted from all logs
+pub fn register_secret(secret: &str) {
+    let secret = secret.trim();
+    // Very short values would redact unrelated text
+    if secret.len() < 8 {
+        return;
+    }
+    let mut secrets = known_secrets().write().unwrap_or_else(|e| e.into_inner());
+    if !secrets.iter().any(|s| s == secret) {
+        secrets.push(secret.to_string());
+    }
+}
+
+/// Replace tokens, OAuth codes and client secrets in `input` with `[REDACTED]`
+pub fn redact(input: &str) -> Cow<'_, str> {
+    let mut output = Cow::Borrowed(input);
+
+    for pattern in secret_patterns() {
+        if let Cow::Owned(replaced) =
+            pattern.replace_all(&output, format!("${{1}}{}", REDACTED).as_str())
+        {
+            output = Cow::Owned(replaced);
+        }
+    }
+
+    let secrets = known_secrets().read().unwrap_or_else(|e| e.into_inner());
+    for secret in secrets.iter() {
+        if output.contains(secret.as_str()) {
+            output = Cow::Owned(output.replace(secret.as_str(), REDACTED));
+        }
+    }
+
+    output
+}
+
+/// Writer that redacts each formatted log event before passing it on
+pub struct RedactingWriter<W> {
+    inner: W,
+}
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // The fmt layer writes one complete event per call
+        let text = String::from_utf8_lossy(buf);
+        self.inner.write_all(redact(&text).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// [`MakeWriter`] wrapper producing [`RedactingWriter`]s
+pub struct Redacting<M>(pub M);
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for Redacting<M> {
+    type Writer = RedactingWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter {
+            inner: self.0.make_writer(),
+        }
+    }
+}
+
+/// Filter directives for a verbosity level (`-v` count)
+fn verbosity_directives(verbosity: u8) -> String {
+    let level = match verbosity {
+        0 => return "off".to_string(),
+        1 => DEFAULT_LOG_LEVEL,
+        2 => "debug",
+        _ => return "trace".to_string(),
+    };
+    format!("warn,tickrs={level},ticktickrs={level}")
+}
+
+/// Build a filter from `RUST_LOG` if set, otherwise from the verbosity level
+fn filter_for(verbosity: u8) -> EnvFilter {
+    match std::env::var(ENV_LOG_LEVEL) {
+        Ok(directives) if !directives.trim().is_empty() => EnvFilter::new(directives),
+        _ => EnvFilter::new(verbosity_directives(verbosity)),
+    }
+}
+
+/// Install the global tracing subscriber.
+///
+/// Without `-v` and `RUST_LOG`, nothing is logged to stderr. `-v` shows
+/// informational messages such as retries, `-vv` adds request/response
+/// details, and `-vvv` enables trace output from all libraries. If a log file
+/// is given, JSON lines are appended to it at debug level (or `RUST_LOG`)
+/// or more verbose if requested.
+pub fn init(verbosity: u8, log_file: Option<&Path>) -> Result<()> {
+    let stderr_layer = tracing_subscriber::fmt::layer()
+        .with_writer(Redacting(io::stderr))
+        .with_filter(filter_for(verbosity));
+
+    let file_layer = match log_file {
+        Some(path) => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("Failed to open log file: {}", path.display()))?;
+            Some(
+                tracing_subscriber::fmt::layer()
+                    .json()
+                    .with_writer(Redacting(file))
+                    .with_filter(filter_for(verbosity.max(2))),
+            )
+        }
+        None => None,
+    };
+
+    tracing_subscriber::registry()
+        .with(stderr_layer)
+        .with(file_layer)
+        .try_init()
+        .context("Failed to initialize logging")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_bearer_token() {
+        assert_eq!(
+            redact("authorization: Bearer abc.DEF-123_xyz"),
+            "authorization: Bearer [REDACTED]"
+        );
+    }
+
+    #[test]
+    fn test_redact_query_and_form_values() {
+        assert_eq!(
+            redact("GET /?code=abc123&state=xyz HTTP/1.1"),
+            "GET /?code=[REDACTED]&state=xyz HTTP/1.1"
+        );
+        assert_eq!(
+            redact("grant_type=authorization_code&client_secret=s3cr3t&code=c0de"),
+            "grant_type=authorization_code&client_secret=[REDACTED]&code=[REDACTED]"
+        );
+    }
+
+    #[test]
+    fn test_redact_json_values() {
+        assert_eq!(
+            redact(r#"{"access_token":"tok","refresh_token": "ref","scope":"tasks:read"}"#),
+            r#"{"access_token":"[REDACTED]","refresh_token": "[REDACTED]","scope":"tasks:read"}"#
+        );
+    }
+
+    #[test]
+    fn test_redact_leaves_unrelated_text() {
+        let text = r#"GET /project/abc/data 200 {"errorCode":"task_not_found"}"#;
+        assert!(matches!(redact(text), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_redact_registered_secret() {
+        register_secret("registered-secret-value-42");
+        assert_eq!(
+            redact("token registered-secret-value-42 leaked"),
+            format!("token {} leaked", REDACTED)
+        );
+    }
+
+    #[test]
+    fn test_register_ignores_short_values() {
+        register_secret("abc");
+        assert_eq!(redact("abc"), "abc");
+    }
+
+    #[test]
+    fn test_redacting_writer() {
+        let mut buffer = Vec::new();
+        {
+            let mut writer = RedactingWriter { inner: &mut buffer };
+            writer
+                .write_all(b"Authorization: Bearer secret-token\n")
+                .unwrap();
+        }
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "Authorization: Bearer [REDACTED]\n"
+        );
+    }
+
+    #[test]
+    fn test_verbosity_directives() {
+        assert_eq!(verbosity_directives(0), "off");
+        assert!(verbosity_directives(1).contains("tickrs=info"));
+        assert!(verbosity_directives(2).contains("tickrs=debug"));
+        assert_eq!(verbosity_directives(5), "trace");
+    }
+}
@@ -3,6 +3,8 @@
 //! This module contains shared utilities including:
 //! - Date parsing for natural language dates
 //! - Error types and conversions
+//! - Logging setup with secret redaction
 
 pub mod date_parser;
 pub mod error;
+pub mod logging;
@@ -1,11 +1,16 @@
 //! Integration tests for TickTick API client using mock server
 
-use wiremock::matchers::{bearer_token, header, method, path};
+use std::time::Duration;
+use wiremock::matchers::{bearer_token, body_string_contains, header, method, path};
+
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
+use ticktickrs::config::{Endpoints, HttpConfig, TokenSet};
+
+use ticktickrs::api::read_only::ReadOnlyReason;
 use ticktickrs::api::{
-    CreateProjectRequest, CreateTaskRequest, TickTickClient, UpdateProjectRequest,
-    UpdateTaskRequest,
+    ApiError, AuthHandler, CreateProjectRequest, CreateTaskRequest, ResponseCache, RetryPolicy,
+    TickTickClient, TokenRefresher, UpdateProjectRequest, UpdateTaskRequest,
 };
 use ticktickrs::models::{ChecklistItemRequest, Priority};
 
@@ -498,6 +503,73 @@ async fn test_bad_request_error() {
     assert!(err.to_string().contains("Bad request"));
 }
 
+#[tokio::test]
+async fn test_upstream_error_fields_are_parsed() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/task"))
+        .respond_with(ResponseTemplate::new(400).set_body_string(
+            r#"{"errorId":"x1","errorCode":"invalid_param","errorMessage":"title is required"}"#,
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let client = test_client(&mock_server);
+    let request = CreateTaskRequest {
+        title: String::new(),
+        project_id: "proj1".to_string(),
+        content: None,
+        is_all_day: None,
+        start_date: None,
+        due_date: None,
+        priority: None,
+        time_zone: None,
+        tags: None,
+        items: None,
+    };
+
+    let err = client.create_task(&request).await.unwrap_err();
+    assert_eq!(err.to_string(), "Bad request: title is required");
+
+    let details = err.details().unwrap();
+    assert_eq!(details["error_code"], "invalid_param");
+    assert_eq!(details["status"], 400);
+    assert_eq!(details["method"], "POST");
+    assert_eq!(details["endpoint"], "/task");
+}
+
+#[tokio::test]
+async fn test_upstream_not_found_code_maps_to_not_found() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/project/proj1/task/missing"))
+        .respond_with(
+            ResponseTemplate::new(500).set_body_string(
+                r#"{"errorCode":"task_not_found","errorMessage":"Task not found"}"#,
+            ),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let client = fast_retry_client(&mock_server, RetryPolicy::none());
+    let err = client.get_task("proj1", "missing").await.unwrap_err();
+
+    match &err {
+        ApiError::NotFound(info) => {
+            assert_eq!(info.error_code.as_deref(), Some("task_not_found"));
+            assert_eq!(info.status, Some(500));
+            assert_eq!(
+                info.endpoint.as_deref(),
+                Some("/project/proj1/task/missing")
+            );
+        }
+        other => panic!("expected NotFound, got {:?}", other),
+    }
+    assert_eq!(err.to_string(), "Resource not found: Task not found");
+}
+
 #[tokio::test]
 async fn test_invalid_json_response() {
     let mock_server = MockServer::start().await;
@@ -516,6 +588,490 @@ async fn test_invalid_json_response() {
     assert!(err.to_string().contains("parse"));
 }
 
+// =============================================================================
+// Retry Tests
+// =============================================================================
+
+/// Helper to create a test client that retries without noticeable delays
+fn fast_retry_client(server: &MockServer, policy: RetryPolicy) -> TickTickClient {
+    test_client(server).with_retry_policy(policy.with_base_delay(Duration::from_millis(1)))
+}
+
+#[tokio::test]
+async fn test_retry_after_rate_limit_then_success() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/project"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/project"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("[]"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = fast_retry_client(&mock_server, RetryPolicy::default());
+    let projects = client.list_projects().await.unwrap();
+
+    // Only INBOX, added by the client
+    assert_eq!(projects.len(), 1);
+}
+
+#[tokio::test]
+async fn test_retry_gives_up_after_max_attempts() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/project"))
+        .respond_with(ResponseTemplate::new(503).set_body_string("Service Unavailable"))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let client = fast_retry_client(&mock_server, RetryPolicy::default().with_max_attempts(2));
+    let err = client.list_projects().await.unwrap_err();
+
+    assert!(matches!(err, ApiError::Retried { attempts: 2, .. }));
+    assert!(err.to_string().contains("Server error"));
+    assert_eq!(err.details().unwrap()["attempts"], 2);
+}
+
+#[tokio::test]
+async fn test_retry_after_too_long_is_not_retried() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/project"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "3600"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = fast_retry_client(&mock_server, RetryPolicy::default());
+    let err = client.list_projects().await.unwrap_err();
+
+    assert!(matches!(err, ApiError::RateLimited { .. }));
+    assert_eq!(err.details().unwrap()["retry_after_secs"], 3600);
+}
+
+#[tokio::test]
+async fn test_post_not_retried_by_default() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/project"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = fast_retry_client(&mock_server, RetryPolicy::default());
+    let request = CreateProjectRequest {
+        name: "Work".to_string(),
+        color: None,
+        view_mode: None,
+        kind: None,
+    };
+
+    let err = client.create_project(&request).await.unwrap_err();
+    assert!(matches!(err, ApiError::ServerError(_)));
+}
+
+#[tokio::test]
+async fn test_post_retried_when_opted_in() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/project"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/project"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(r#"{"id": "proj1", "name": "Work"}"#),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let client = fast_retry_client(
+        &mock_server,
+        RetryPolicy::default().with_retry_non_idempotent(true),
+    );
+    let request = CreateProjectRequest {
+        name: "Work".to_string(),
+        color: None,
+        view_mode: None,
+        kind: None,
+    };
+
+    let project = client.create_project(&request).await.unwrap();
+    assert_eq!(project.id, "proj1");
+}
+
+#[tokio::test]
+async fn test_read_timeout_aborts_stalled_request() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/project"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("[]")
+                .set_delay(Duration::from_secs(5)),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let http_config = HttpConfig {
+        read_timeout_secs: 1,
+        ..HttpConfig::default()
+    };
+    let client = test_client(&mock_server)
+        .with_http_config(&http_config)
+        .unwrap()
+        .with_retry_policy(RetryPolicy::none());
+
+    let err = client.list_projects().await.unwrap_err();
+    match err {
+        ApiError::NetworkError(e) => assert!(e.is_timeout()),
+        other => panic!("expected timeout, got {:?}", other),
+    }
+}
+
+// =============================================================================
+// Batch Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_batch_returns_results_in_input_order() {
+    let mock_server = MockServer::start().await;
+
+    // The first project responds last
+    for (id, delay_ms) in [("slow", 300), ("medium", 100), ("fast", 0)] {
+        Mock::given(method("GET"))
+            .and(path(format!("/project/{}/data", id)))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(format!(
+                        r#"{{"tasks": [{{"id": "task-{}", "projectId": "{}", "title": "Task"}}]}}"#,
+                        id, id
+                    ))
+                    .set_delay(Duration::from_millis(delay_ms)),
+            )
+            .mount(&mock_server)
+            .await;
+    }
+
+    let client = test_client(&mock_server).with_max_in_flight(3);
+    let project_ids = ["slow", "medium", "fast"];
+    let results = client.batch(project_ids, |id| client.list_tasks(id)).await;
+
+    let task_ids: Vec<String> = results
+        .into_iter()
+        .map(|tasks| tasks.unwrap().remove(0).id)
+        .collect();
+    assert_eq!(task_ids, ["task-slow", "task-medium", "task-fast"]);
+}
+
+#[tokio::test]
+async fn test_batch_keeps_individual_errors() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/project/ok/data"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"tasks": []}"#))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/project/missing/data"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&mock_server)
+        .await;
+
+    let client = test_client(&mock_server);
+    let results = client
+        .batch(["missing", "ok"], |id| client.list_tasks(id))
+        .await;
+
+    assert!(matches!(results[0], Err(ApiError::NotFound(_))));
+    assert!(results[1].as_ref().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_client_is_not_rate_limited_by_default() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/project"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("[]"))
+        .expect(40)
+        .mount(&mock_server)
+        .await;
+
+    // At the CLI's default of 10 requests per second this would take 4 seconds
+    let client = test_client(&mock_server).with_max_in_flight(8);
+    let start = std::time::Instant::now();
+    let results = client.batch(0..40, |_| client.list_projects()).await;
+
+    assert!(results.iter().all(|r| r.is_ok()));
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
+
+// =============================================================================
+// Response Cache Tests
+// =============================================================================
+
+/// Helper to create a test client with an on-disk cache in a temporary directory
+fn cached_client(server: &MockServer, cache_dir: &tempfile::TempDir) -> TickTickClient {
+    test_client(server).with_cache(ResponseCache::new(
+        cache_dir.path(),
+        Duration::from_secs(60),
+    ))
+}
+
+#[tokio::test]
+async fn test_cached_reads_are_served_from_disk() {
+    let mock_server = MockServer::start().await;
+    let cache_dir = tempfile::tempdir().unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/project"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("[]"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/project/proj1/data"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"tasks": []}"#))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    // A second client shares the cache directory, like a later CLI invocation
+    for _ in 0..2 {
+        let client = cached_client(&mock_server, &cache_dir);
+        assert_eq!(client.list_projects().await.unwrap().len(), 1);
+        assert!(client.list_tasks("proj1").await.unwrap().is_empty());
+    }
+}
+
+#[tokio::test]
+async fn test_mutation_invalidates_cache() {
+    let mock_server = MockServer::start().await;
+    let cache_dir = tempfile::tempdir().unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/project"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("[]"))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("DELETE"))
+        .and(path("/project/proj1"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let client = cached_client(&mock_server, &cache_dir);
+    client.list_projects().await.unwrap();
+    client.list_projects().await.unwrap();
+    client.delete_project("proj1").await.unwrap();
+    client.list_projects().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_error_responses_are_not_cached() {
+    let mock_server = MockServer::start().await;
+    let cache_dir = tempfile::tempdir().unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/project/missing"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let client = cached_client(&mock_server, &cache_dir);
+    assert!(client.get_project("missing").await.is_err());
+    assert!(client.get_project("missing").await.is_err());
+}
+
+#[tokio::test]
+async fn test_cache_bypass_fetches_fresh_data() {
+    let mock_server = MockServer::start().await;
+    let cache_dir = tempfile::tempdir().unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/project"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("[]"))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let cache = ResponseCache::new(cache_dir.path(), Duration::from_secs(60));
+    let client = test_client(&mock_server).with_cache(cache.clone());
+    client.list_projects().await.unwrap();
+
+    let bypassing = test_client(&mock_server).with_cache(cache.with_bypass(true));
+    bypassing.list_projects().await.unwrap();
+}
+
+// =============================================================================
+// Token Refresh Tests
+// =============================================================================
+
+/// Helper to create a refresher using the mock server as token endpoint
+fn test_refresher(server: &MockServer, tokens: TokenSet) -> TokenRefresher {
+    let endpoints = Endpoints::new(&server.uri(), &server.uri()).unwrap();
+    let auth =
+        AuthHandler::new("client".to_string(), "secret".to_string()).with_endpoints(endpoints);
+    TokenRefresher::new(auth, tokens)
+}
+
+fn refreshable_tokens() -> TokenSet {
+    let mut tokens = TokenSet::new("test_token");
+    tokens.refresh_token = Some("refresh_token".to_string());
+    tokens.scopes = vec!["tasks:read".to_string(), "tasks:write".to_string()];
+    tokens
+}
+
+async fn mount_token_endpoint(server: &MockServer, expected_calls: u64) {
+    Mock::given(method("POST"))
+        .and(path("/oauth/token"))
+        .and(body_string_contains("grant_type=refresh_token"))
+        .and(body_string_contains("refresh_token=refresh_token"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            r#"{"access_token": "new_token", "token_type": "bearer", "expires_in": 3600}"#,
+            "application/json",
+        ))
+        .expect(expected_calls)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_unauthorized_response_refreshes_token() {
+    let mock_server = MockServer::start().await;
+    mount_token_endpoint(&mock_server, 1).await;
+
+    Mock::given(method("GET"))
+        .and(path("/project"))
+        .and(bearer_token("test_token"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/project"))
+        .and(bearer_token("new_token"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("[]"))
+        .mount(&mock_server)
+        .await;
+
+    let refresher = test_refresher(&mock_server, refreshable_tokens());
+    let client = test_client(&mock_server).with_token_refresher(refresher);
+    assert_eq!(client.list_projects().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_refreshed_tokens_keep_refresh_token_and_scopes() {
+    let mock_server = MockServer::start().await;
+    mount_token_endpoint(&mock_server, 1).await;
+
+    let refresher = test_refresher(&mock_server, refreshable_tokens());
+    assert_eq!(refresher.refresh("test_token").await.unwrap(), "new_token");
+
+    let tokens = refresher.tokens().await;
+    assert_eq!(tokens.access_token, "new_token");
+    assert_eq!(tokens.refresh_token.as_deref(), Some("refresh_token"));
+    assert_eq!(tokens.scopes, ["tasks:read", "tasks:write"]);
+    assert!(tokens.expires_at.is_some());
+    assert_eq!(tokens.client_id.as_deref(), Some("client"));
+    assert_eq!(tokens.client_secret, None);
+}
+
+#[tokio::test]
+async fn test_expiring_token_is_refreshed_before_request() {
+    let mock_server = MockServer::start().await;
+    mount_token_endpoint(&mock_server, 1).await;
+
+    Mock::given(method("GET"))
+        .and(path("/project"))
+        .and(bearer_token("new_token"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("[]"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let mut tokens = refreshable_tokens();
+    tokens.expires_at = Some(chrono::Utc::now() + chrono::Duration::seconds(10));
+    let refresher = test_refresher(&mock_server, tokens);
+    let client = test_client(&mock_server).with_token_refresher(refresher);
+    client.list_projects().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_concurrent_unauthorized_responses_refresh_once() {
+    let mock_server = MockServer::start().await;
+    mount_token_endpoint(&mock_server, 1).await;
+
+    Mock::given(method("GET"))
+        .and(bearer_token("test_token"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(bearer_token("new_token"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"tasks": []}"#))
+        .mount(&mock_server)
+        .await;
+
+    let refresher = test_refresher(&mock_server, refreshable_tokens());
+    let client = test_client(&mock_server).with_token_refresher(refresher);
+    let results = client
+        .batch(["a", "b", "c", "d"], |id| client.list_tasks(id))
+        .await;
+    assert!(results.iter().all(|result| result.is_ok()));
+}
+
+#[tokio::test]
+async fn test_failed_refresh_reports_auth_error() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/oauth/token"))
+        .respond_with(ResponseTemplate::new(400).set_body_string(r#"{"error": "invalid_grant"}"#))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/project"))
+        .respond_with(ResponseTemplate::new(401))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let refresher = test_refresher(&mock_server, refreshable_tokens());
+    let client = test_client(&mock_server).with_token_refresher(refresher);
+    let err = client.list_projects().await.unwrap_err();
+    assert!(matches!(err, ApiError::TokenRefreshFailed(_)));
+    assert_eq!(err.code().to_string(), "AUTH_EXPIRED");
+    assert!(err.to_string().contains("invalid_grant"));
+}
+
 // =============================================================================
 // Subtask (Checklist Items) Tests
 // =============================================================================
@@ -629,3 +1185,33 @@ async fn test_update_task_add_subtasks() {
     assert_eq!(task.items.len(), 1);
     assert_eq!(task.items[0].title, "New subtask");
 }
+
+// =============================================================================
+// Read-Only Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_read_only_mode_only_applies_when_requested() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("DELETE"))
+        .and(path("/project/proj1"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    // The CLI's --read-only switch doesn't reach clients built directly
+    ticktickrs::api::read_only::enable();
+    test_client(&mock_server)
+        .delete_project("proj1")
+        .await
+        .unwrap();
+
+    let err = test_client(&mock_server)
+        .with_read_only(Some(ReadOnlyReason::Requested))
+        .delete_project("proj1")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ApiError::ReadOnly(ReadOnlyReason::Requested)));
+}
@@ -12,6 +12,7 @@ use wiremock::matchers::{body_string_contains, method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 use ticktickrs::api::AuthHandler;
+use ticktickrs::config::{Config, Endpoints, OAuthConfig, Region};
 
 // =============================================================================
 // Authorization URL Generation Tests
@@ -40,6 +41,33 @@ fn test_auth_url_generation() {
     assert!(url.contains("tasks"));
 }
 
+#[test]
+fn test_auth_url_for_dida365_region() {
+    let config = Config {
+        region: Region::Dida365,
+        ..Config::default()
+    };
+    let endpoints =
+        Endpoints::new(config.region.api_base_url(), config.region.oauth_base_url()).unwrap();
+    let handler = AuthHandler::new("test_client_id".to_string(), "test_secret".to_string())
+        .with_endpoints(endpoints);
+    let (url, _) = handler.get_auth_url().unwrap();
+
+    assert!(url.starts_with("https://dida365.com/oauth/authorize?"));
+    assert!(url.contains("client_id=test_client_id"));
+}
+
+#[test]
+fn test_auth_url_for_custom_oauth_server() {
+    let endpoints =
+        Endpoints::new("http://localhost:9000/open/v1", "http://localhost:9000/").unwrap();
+    let handler = AuthHandler::new("test_client_id".to_string(), "test_secret".to_string())
+        .with_endpoints(endpoints);
+    let (url, _) = handler.get_auth_url().unwrap();
+
+    assert!(url.starts_with("http://localhost:9000/oauth/authorize?"));
+}
+
 #[test]
 fn test_auth_url_contains_response_type() {
     let handler = AuthHandler::new("client123".to_string(), "secret456".to_string());
@@ -60,6 +88,178 @@ fn test_csrf_token_uniqueness() {
     assert_ne!(token1.secret(), token2.secret());
 }
 
+// =============================================================================
+// PKCE Tests
+// =============================================================================
+
+/// Value of a query parameter in an authorization URL
+fn query_param(url: &str, name: &str) -> Option<String> {
+    oauth2::url::Url::parse(url)
+        .unwrap()
+        .query_pairs()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+}
+
+/// Handler talking to a mock OAuth server
+fn mock_handler(server: &MockServer, pkce: bool) -> AuthHandler {
+    let endpoints = Endpoints::new(&format!("{}/open/v1", server.uri()), &server.uri()).unwrap();
+    AuthHandler::new("test_client".to_string(), "test_secret".to_string())
+        .with_endpoints(endpoints)
+        .with_callback_config(OAuthConfig {
+            pkce,
+            ..OAuthConfig::default()
+        })
+}
+
+/// Mock token endpoint response
+fn token_response() -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(
+        r#"{"access_token": "mock_access_token_12345", "token_type": "Bearer", "expires_in": 3600}"#,
+        "application/json",
+    )
+}
+
+#[test]
+fn test_auth_url_contains_pkce_challenge() {
+    let handler = AuthHandler::new("test_client_id".to_string(), "test_secret".to_string());
+    let (url, _) = handler.get_auth_url().unwrap();
+
+    assert_eq!(
+        query_param(&url, "code_challenge_method").as_deref(),
+        Some("S256")
+    );
+    let challenge = query_param(&url, "code_challenge").unwrap();
+    // Base64url-encoded SHA-256 digest without padding
+    assert_eq!(challenge.len(), 43);
+    assert!(challenge
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+}
+
+#[test]
+fn test_pkce_challenge_uniqueness() {
+    let handler = AuthHandler::new("test".to_string(), "test".to_string());
+    let (url1, _) = handler.get_auth_url().unwrap();
+    let (url2, _) = handler.get_auth_url().unwrap();
+
+    assert_ne!(
+        query_param(&url1, "code_challenge"),
+        query_param(&url2, "code_challenge")
+    );
+}
+
+#[test]
+fn test_auth_url_without_pkce() {
+    let handler = AuthHandler::new("test".to_string(), "test".to_string()).with_callback_config(
+        OAuthConfig {
+            pkce: false,
+            ..OAuthConfig::default()
+        },
+    );
+    let (url, _) = handler.get_auth_url().unwrap();
+
+    assert!(query_param(&url, "code_challenge").is_none());
+    assert!(query_param(&url, "code_challenge_method").is_none());
+    assert!(query_param(&url, "state").is_some());
+}
+
+#[tokio::test]
+async fn test_token_exchange_sends_matching_pkce_verifier() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/oauth/token"))
+        .and(body_string_contains("code=auth_code_123"))
+        .and(body_string_contains("code_verifier="))
+        .respond_with(token_response())
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let handler = mock_handler(&mock_server, true);
+    let (url, csrf_token) = handler.get_auth_url().unwrap();
+    let redirect = format!(
+        "http://localhost:8080/?code=auth_code_123&state={}",
+        csrf_token.secret()
+    );
+    let tokens = handler
+        .complete_with_pasted_code(&redirect, &csrf_token)
+        .await
+        .unwrap();
+    assert_eq!(tokens.access_token, "mock_access_token_12345");
+
+    // The verifier sent to the token endpoint hashes to the challenge in the URL
+    let requests = mock_server.received_requests().await.unwrap();
+    let body = String::from_utf8(requests[0].body.clone()).unwrap();
+    let verifier = oauth2::url::form_urlencoded::parse(body.as_bytes())
+        .find(|(key, _)| key == "code_verifier")
+        .map(|(_, value)| value.into_owned())
+        .unwrap();
+    let challenge = oauth2::PkceCodeChallenge::from_code_verifier_sha256(
+        &oauth2::PkceCodeVerifier::new(verifier),
+    );
+    assert_eq!(
+        query_param(&url, "code_challenge").as_deref(),
+        Some(challenge.as_str())
+    );
+}
+
+#[tokio::test]
+async fn test_token_exchange_falls_back_without_pkce() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/oauth/token"))
+        .and(body_string_contains("code_verifier="))
+        .respond_with(ResponseTemplate::new(400).set_body_raw(
+            r#"{"error": "invalid_request", "error_description": "Unsupported parameter: code_verifier"}"#,
+            "application/json",
+        ))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/oauth/token"))
+        .respond_with(token_response())
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let handler = mock_handler(&mock_server, true);
+    let (_, csrf_token) = handler.get_auth_url().unwrap();
+    let tokens = handler
+        .complete_with_pasted_code(
+            &format!("code=auth_code_123&state={}", csrf_token.secret()),
+            &csrf_token,
+        )
+        .await
+        .unwrap();
+    assert_eq!(tokens.access_token, "mock_access_token_12345");
+}
+
+#[tokio::test]
+async fn test_token_exchange_invalid_grant_is_not_retried() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/oauth/token"))
+        .respond_with(ResponseTemplate::new(400).set_body_raw(
+            r#"{"error": "invalid_grant", "error_description": "PKCE verification failed"}"#,
+            "application/json",
+        ))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let handler = mock_handler(&mock_server, true);
+    let (_, csrf_token) = handler.get_auth_url().unwrap();
+    let result = handler
+        .complete_with_pasted_code(
+            &format!("code=auth_code_123&state={}", csrf_token.secret()),
+            &csrf_token,
+        )
+        .await;
+    assert!(result.is_err());
+}
+
 // =============================================================================
 // Token Exchange Tests (with mock server)
 // =============================================================================